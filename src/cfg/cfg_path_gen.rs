@@ -1,9 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct CfgPathGen(Option<PathBuf>);
 
-const DEFAULT_CFG_NAME: &str = "jf.toml";
+pub const DEFAULT_CFG_NAME: &str = "jf.toml";
 
 /// cfg file path Generator
 impl CfgPathGen {
@@ -11,6 +11,18 @@ impl CfgPathGen {
         Self(input)
     }
 
+    /// cfg file path, searching parent directories if not given
+    ///
+    /// Falls back to [`Self::gen`] if no cfg file is found.
+    pub fn find(self) -> PathBuf {
+        if self.0.is_none() {
+            if let Some(path) = std::env::current_dir().ok().and_then(|dir| search(&dir)) {
+                return path;
+            }
+        }
+        self.gen()
+    }
+
     /// parse & generate cfg file path
     pub fn gen(self) -> PathBuf {
         match self.0 {
@@ -26,6 +38,20 @@ impl CfgPathGen {
     }
 }
 
+/// search cfg file in `start` and its ancestors until the root of git repository
+pub fn search(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let path = dir.join(DEFAULT_CFG_NAME);
+        if path.is_file() {
+            return Some(path);
+        }
+        if dir.join(".git").exists() {
+            return None;
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -62,4 +88,37 @@ pub mod tests {
         let path = CfgPathGen::new(Some(file_path.clone())).gen();
         assert_eq!(path, file_path);
     }
+
+    #[test]
+    #[coverage(off)]
+    fn search_ancestors() {
+        let include = fixtures_dir().join("include");
+        let api = include.join("packages").join("api");
+        assert_eq!(search(&api), Some(api.join(DEFAULT_CFG_NAME)));
+        assert_eq!(
+            search(&include.join("packages")),
+            Some(include.join(DEFAULT_CFG_NAME))
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn search_stops_at_git_root() -> std::io::Result<()> {
+        let root = std::env::temp_dir().join(format!("jf-search-{}", std::process::id()));
+        let nested = root.join("a").join("b");
+        std::fs::create_dir_all(root.join(".git"))?;
+        std::fs::create_dir_all(&nested)?;
+        let found = search(&nested);
+        std::fs::remove_dir_all(&root)?;
+        assert_eq!(found, None);
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn find_given() {
+        let dir = fixtures_dir();
+        let path = CfgPathGen::new(Some(dir.clone())).find();
+        assert_eq!(path, dir.join(DEFAULT_CFG_NAME));
+    }
 }
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
 use serde::Deserialize;
 
-use super::Visibility;
+use super::{ParamCfg, Visibility};
+use crate::{job::modes::RetryParams, util::duration};
 
 #[derive(Deserialize)]
 pub struct CommonCfg {
@@ -9,6 +12,24 @@ pub struct CommonCfg {
     visibility: Visibility,
     #[serde(default)]
     description: String,
+    #[serde(default)]
+    params: HashMap<String, ParamCfg>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    retry: Option<RetryParams>,
+    #[serde(default, deserialize_with = "duration::deserialize_opt")]
+    timeout: Option<Duration>,
+    #[serde(default)]
+    watch_list: Vec<String>,
+    /// given by `include` (e.g. `api:db`), `None` for jobs of the root cfg file
+    #[serde(skip)]
+    namespace: Option<String>,
+    /// `[env]` of the included cfg files defining the job
+    #[serde(skip)]
+    file_env: HashMap<String, String>,
+    /// directory which relative `watch_list` belongs to, set on load
+    #[serde(skip)]
+    base: Option<PathBuf>,
 }
 
 impl CommonCfg {
@@ -19,6 +40,51 @@ impl CommonCfg {
     pub fn description(&self) -> String {
         self.description.clone()
     }
+
+    pub fn params(&self) -> &HashMap<String, ParamCfg> {
+        &self.params
+    }
+
+    pub fn depends_on(&self) -> &Vec<String> {
+        &self.depends_on
+    }
+
+    pub fn retry(&self) -> Option<&RetryParams> {
+        self.retry.as_ref()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn watch_list(&self) -> &Vec<String> {
+        &self.watch_list
+    }
+
+    pub fn base(&self) -> Option<&PathBuf> {
+        self.base.as_ref()
+    }
+
+    pub fn set_base(&mut self, base: PathBuf) {
+        self.base = Some(base);
+    }
+
+    pub fn namespace(&self) -> Option<&String> {
+        self.namespace.as_ref()
+    }
+
+    pub fn file_env(&self) -> &HashMap<String, String> {
+        &self.file_env
+    }
+
+    /// nest in `namespace` of the including cfg file, whose `env` takes precedence
+    pub fn include(&mut self, namespace: &str, env: &HashMap<String, String>) {
+        self.namespace = Some(match self.namespace.take() {
+            Some(inner) => format!("{namespace}:{inner}"),
+            None => namespace.to_owned(),
+        });
+        self.file_env.extend(env.clone());
+    }
 }
 
 #[cfg(test)]
@@ -28,6 +94,14 @@ impl CommonCfg {
         Self {
             visibility,
             description,
+            params: HashMap::new(),
+            depends_on: Vec::new(),
+            retry: None,
+            timeout: None,
+            watch_list: Vec::new(),
+            namespace: None,
+            file_env: HashMap::new(),
+            base: None,
         }
     }
 }
@@ -44,6 +118,14 @@ mod tests {
             Self {
                 visibility: Default::default(),
                 description: "".into(),
+                params: HashMap::new(),
+                depends_on: Vec::new(),
+                retry: None,
+                timeout: None,
+                watch_list: Vec::new(),
+                namespace: None,
+                file_env: HashMap::new(),
+                base: None,
             }
         }
     }
@@ -87,4 +169,61 @@ description = "test2"
         assert_eq!(cfg.description, "test2");
         Ok(())
     }
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize_params() -> JfResult<()> {
+        let cfg: CommonCfg = toml::from_str(
+            r#"
+params = { target = { default = "debug", choices = ["debug", "release"] } }
+"#,
+        )?;
+
+        assert_eq!(cfg.params().len(), 1);
+        assert!(cfg.depends_on().is_empty());
+        assert!(cfg.retry().is_none());
+        assert!(cfg.params()["target"].check("target", "release").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize_depends_on() -> JfResult<()> {
+        let cfg: CommonCfg = toml::from_str(r#"depends_on = ["build", "gen(lang=rust)"]"#)?;
+
+        assert_eq!(cfg.depends_on(), &vec!["build", "gen(lang=rust)"]);
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize_retry() -> JfResult<()> {
+        let cfg: CommonCfg = toml::from_str(
+            r#"retry = { attempts = 3, backoff = "exponential", initial_ms = 500 }"#,
+        )?;
+
+        assert_eq!(cfg.retry().map(|r| r.attempts.get()), Some(3));
+        assert!(toml::from_str::<CommonCfg>("retry = { attempts = 0 }").is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize_timeout() -> JfResult<()> {
+        let cfg: CommonCfg = toml::from_str(r#"timeout = "10m""#)?;
+
+        assert_eq!(cfg.timeout(), Some(Duration::from_secs(600)));
+        assert!(toml::from_str::<CommonCfg>("").map(|c| c.timeout().is_none())?);
+        assert!(toml::from_str::<CommonCfg>(r#"timeout = "10 minutes""#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize_watch_list() -> JfResult<()> {
+        let cfg: CommonCfg = toml::from_str(r#"watch_list = ["src/**"]"#)?;
+
+        assert_eq!(cfg.watch_list(), &vec!["src/**"]);
+        Ok(())
+    }
 }
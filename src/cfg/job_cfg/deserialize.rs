@@ -3,6 +3,77 @@ use super::{
     modes::{CommandCfg, ParallelCfg, SequentialCfg, ShellCfg, WatchCfg},
     JobCfg,
 };
+use crate::util::suggest::did_you_mean;
+
+/// values of `mode`
+pub const MODES: &[&str] = &["command", "parallel", "sequential", "shell", "watch"];
+
+/// keys of [`super::common::CommonCfg`] accepted by every mode
+const COMMON_KEYS: &[&str] = &[
+    "visibility",
+    "description",
+    "params",
+    "depends_on",
+    "retry",
+    "timeout",
+    "watch_list",
+];
+
+/// error message for `mode` which is not in [`MODES`]
+pub fn unknown_mode(mode: &str) -> String {
+    match did_you_mean(mode, MODES.iter().copied()) {
+        Some(suggestion) => format!("unknown mode `{mode}`\nDid you mean `{suggestion}`?"),
+        None => format!(
+            "unknown mode `{mode}`\nAvailable modes: {}",
+            MODES.join(", ")
+        ),
+    }
+}
+
+impl JobCfg {
+    /// keys of `*Params` of `mode` (`None` if `mode` is unknown)
+    ///
+    /// Update them with the fields of the structs (checked by tests).
+    fn params_keys(mode: &str) -> Option<&'static [&'static str]> {
+        Some(match mode {
+            "command" => &["command", "args", "env", "env_file", "clear_env", "cwd"],
+            "parallel" => &[
+                "jobs",
+                "cwd",
+                "forward_args",
+                "on_failure",
+                "max_concurrency",
+            ],
+            "sequential" => &[
+                "jobs",
+                "cwd",
+                "forward_args",
+                "continue_on_error",
+                "finally",
+            ],
+            "shell" => &["script", "args", "env", "env_file", "clear_env", "cwd"],
+            "watch" => &[
+                "job",
+                "watch_list",
+                "cwd",
+                "debounce_ms",
+                "ignore",
+                "gitignore",
+                "on_change",
+                "backend",
+                "poll_interval_ms",
+            ],
+            #[cfg(test)]
+            "mock" => &["each_sleep_time", "sleep_count"],
+            _ => return None,
+        })
+    }
+
+    /// keys accepted by jobs of `mode` (`None` if `mode` is unknown)
+    pub fn keys(mode: &str) -> Option<Vec<&'static str>> {
+        Some([&["mode"], COMMON_KEYS, Self::params_keys(mode)?].concat())
+    }
+}
 
 impl<'de> serde::Deserialize<'de> for JobCfg {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -38,7 +109,7 @@ impl<'de> serde::Deserialize<'de> for JobCfg {
             "mock" => Ok(Self::Mock(
                 super::modes::MockCfg::deserialize(value).map_err(serde::de::Error::custom)?,
             )),
-            m => Err(serde::de::Error::custom(format!("Unknown mode: {m}"))),
+            m => Err(serde::de::Error::custom(unknown_mode(m))),
         }
     }
 }
@@ -126,5 +197,56 @@ mod tests {
     #[coverage(off)]
     fn unknown() {
         assert!(toml::from_str::<JobCfg>(generate_modable_cfg("unknown", "").as_str()).is_err());
+        assert!(
+            toml::from_str::<JobCfg>(generate_modable_cfg("paralel", "").as_str())
+                .err()
+                .is_some_and(|e| e.to_string().contains("Did you mean `parallel`?"))
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn keys() {
+        for mode in MODES {
+            let keys = JobCfg::keys(mode).unwrap();
+            assert!(keys.contains(&"mode"));
+            assert!(keys.contains(&"description"), "{mode}");
+        }
+        assert!(JobCfg::keys("command").unwrap().contains(&"args"));
+        assert!(JobCfg::keys("watch").unwrap().contains(&"on_change"));
+        assert!(!JobCfg::keys("command").unwrap().contains(&"script"));
+        assert_eq!(JobCfg::keys("unknown"), None);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn keys_match_structs() {
+        use crate::{
+            cfg::job_cfg::common::CommonCfg,
+            job::modes::{
+                CommandParams, MockParams, ParallelParams, SequentialParams, ShellParams,
+                WatchParams,
+            },
+            util::fields::field_names,
+        };
+
+        let sorted = |keys: &[&'static str]| {
+            assert!(!keys.is_empty());
+            let mut keys = keys.to_vec();
+            keys.sort();
+            keys
+        };
+        assert_eq!(sorted(COMMON_KEYS), sorted(field_names::<CommonCfg>()));
+        for (mode, fields) in [
+            ("command", field_names::<CommandParams>()),
+            ("parallel", field_names::<ParallelParams>()),
+            ("sequential", field_names::<SequentialParams>()),
+            ("shell", field_names::<ShellParams>()),
+            ("watch", field_names::<WatchParams>()),
+            ("mock", field_names::<MockParams>()),
+        ] {
+            let keys = JobCfg::params_keys(mode).unwrap();
+            assert_eq!(sorted(keys), sorted(fields), "{mode}");
+        }
     }
 }
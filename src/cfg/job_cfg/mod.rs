@@ -2,9 +2,18 @@
 mod common;
 mod deserialize;
 mod modes;
+mod param_cfg;
 mod visibility;
 
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+pub use self::deserialize::unknown_mode;
+pub use self::param_cfg::ParamCfg;
 pub use self::visibility::Visibility;
+use crate::job::modes::{CommandParams, RetryParams};
 #[cfg(test)]
 pub use common::CommonCfg;
 #[cfg(test)]
@@ -23,6 +32,19 @@ pub enum JobCfg {
 }
 
 impl JobCfg {
+    /// value of `mode`
+    pub fn mode(&self) -> &'static str {
+        match self {
+            JobCfg::Command(_) => "command",
+            JobCfg::Parallel(_) => "parallel",
+            JobCfg::Sequential(_) => "sequential",
+            JobCfg::Shell(_) => "shell",
+            JobCfg::Watch(_) => "watch",
+            #[cfg(test)]
+            JobCfg::Mock(_) => "mock",
+        }
+    }
+
     pub fn visibility(&self) -> &Visibility {
         match self {
             JobCfg::Command(c) => c.common.visibility(),
@@ -35,6 +57,221 @@ impl JobCfg {
         }
     }
 
+    pub fn params(&self) -> &HashMap<String, ParamCfg> {
+        match self {
+            JobCfg::Command(c) => c.common.params(),
+            JobCfg::Parallel(p) => p.common.params(),
+            JobCfg::Sequential(s) => s.common.params(),
+            JobCfg::Shell(s) => s.common.params(),
+            JobCfg::Watch(w) => w.common.params(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.params(),
+        }
+    }
+
+    /// jobs which must succeed before this job starts
+    pub fn depends_on(&self) -> &Vec<String> {
+        match self {
+            JobCfg::Command(c) => c.common.depends_on(),
+            JobCfg::Parallel(p) => p.common.depends_on(),
+            JobCfg::Sequential(s) => s.common.depends_on(),
+            JobCfg::Shell(s) => s.common.depends_on(),
+            JobCfg::Watch(w) => w.common.depends_on(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.depends_on(),
+        }
+    }
+
+    pub fn retry(&self) -> Option<&RetryParams> {
+        match self {
+            JobCfg::Command(c) => c.common.retry(),
+            JobCfg::Parallel(p) => p.common.retry(),
+            JobCfg::Sequential(s) => s.common.retry(),
+            JobCfg::Shell(s) => s.common.retry(),
+            JobCfg::Watch(w) => w.common.retry(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.retry(),
+        }
+    }
+
+    /// files to watch with `jf --watch` (`watch_list` of `watch` mode itself)
+    pub fn watch_list(&self) -> &Vec<String> {
+        match self {
+            JobCfg::Command(c) => c.common.watch_list(),
+            JobCfg::Parallel(p) => p.common.watch_list(),
+            JobCfg::Sequential(s) => s.common.watch_list(),
+            JobCfg::Shell(s) => s.common.watch_list(),
+            JobCfg::Watch(w) => &w.params.watch_list,
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.watch_list(),
+        }
+    }
+
+    /// jobs referenced by this job with the key referencing them
+    pub fn references(&self) -> Vec<(&'static str, &String)> {
+        let mut references = match self {
+            JobCfg::Parallel(p) => p.params.jobs.iter().map(|j| ("jobs", j)).collect(),
+            JobCfg::Sequential(s) => {
+                let jobs = s.params.jobs.iter().map(|j| ("jobs", j));
+                jobs.chain(s.params.finally.iter().map(|j| ("finally", j)))
+                    .collect()
+            }
+            JobCfg::Watch(w) => vec![("job", &w.params.job)],
+            _ => Vec::new(),
+        };
+        references.extend(self.depends_on().iter().map(|j| ("depends_on", j)));
+        references
+    }
+
+    /// `jobs` of `parallel` and `sequential`
+    pub fn jobs(&self) -> Option<&Vec<String>> {
+        match self {
+            JobCfg::Parallel(p) => Some(&p.params.jobs),
+            JobCfg::Sequential(s) => Some(&s.params.jobs),
+            _ => None,
+        }
+    }
+
+    pub fn command(&self) -> Option<&CommandParams> {
+        match self {
+            JobCfg::Command(c) => Some(&c.params),
+            _ => None,
+        }
+    }
+
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            JobCfg::Command(c) => c.common.timeout(),
+            JobCfg::Parallel(p) => p.common.timeout(),
+            JobCfg::Sequential(s) => s.common.timeout(),
+            JobCfg::Shell(s) => s.common.timeout(),
+            JobCfg::Watch(w) => w.common.timeout(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.timeout(),
+        }
+    }
+
+    /// namespace given by `include` (`None` for jobs of the root cfg file)
+    pub fn namespace(&self) -> Option<&String> {
+        match self {
+            JobCfg::Command(c) => c.common.namespace(),
+            JobCfg::Parallel(p) => p.common.namespace(),
+            JobCfg::Sequential(s) => s.common.namespace(),
+            JobCfg::Shell(s) => s.common.namespace(),
+            JobCfg::Watch(w) => w.common.namespace(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.namespace(),
+        }
+    }
+
+    /// `[env]` of the included cfg files defining the job
+    pub fn file_env(&self) -> &HashMap<String, String> {
+        match self {
+            JobCfg::Command(c) => c.common.file_env(),
+            JobCfg::Parallel(p) => p.common.file_env(),
+            JobCfg::Sequential(s) => s.common.file_env(),
+            JobCfg::Shell(s) => s.common.file_env(),
+            JobCfg::Watch(w) => w.common.file_env(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.file_env(),
+        }
+    }
+
+    /// mark job of a cfg file included with `namespace` and `[env]` of the file
+    ///
+    /// Called from the innermost include, so outer namespaces and `[env]` come later.
+    pub fn include(&mut self, namespace: &str, env: &HashMap<String, String>) {
+        match self {
+            JobCfg::Command(c) => c.common.include(namespace, env),
+            JobCfg::Parallel(p) => p.common.include(namespace, env),
+            JobCfg::Sequential(s) => s.common.include(namespace, env),
+            JobCfg::Shell(s) => s.common.include(namespace, env),
+            JobCfg::Watch(w) => w.common.include(namespace, env),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.include(namespace, env),
+        }
+    }
+
+    /// working directory given to the job (`None` if inherited)
+    pub fn cwd(&self) -> Option<&PathBuf> {
+        match self {
+            JobCfg::Command(c) => c.params.cwd.as_ref(),
+            JobCfg::Parallel(p) => p.params.cwd.as_ref(),
+            JobCfg::Sequential(s) => s.params.cwd.as_ref(),
+            JobCfg::Shell(s) => s.params.cwd.as_ref(),
+            JobCfg::Watch(w) => w.params.cwd.as_ref(),
+            #[cfg(test)]
+            JobCfg::Mock(_) => None,
+        }
+    }
+
+    /// directory which relative `watch_list` belongs to (`cwd` of the job or dir of cfg file)
+    pub fn base(&self) -> Option<&PathBuf> {
+        match self {
+            JobCfg::Command(c) => c.common.base(),
+            JobCfg::Parallel(p) => p.common.base(),
+            JobCfg::Sequential(s) => s.common.base(),
+            JobCfg::Shell(s) => s.common.base(),
+            JobCfg::Watch(w) => w.common.base(),
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.base(),
+        }
+    }
+
+    /// resolve relative paths (`cwd`, `env_file`, base of `watch_list`) from `dir` of cfg file
+    pub fn resolve_paths(&mut self, dir: &Path) {
+        let resolve = |path: &mut Option<PathBuf>| {
+            if let Some(p) = path.as_mut() {
+                *p = dir.join(&p);
+            }
+        };
+        let base = |cwd: &Option<PathBuf>| cwd.clone().unwrap_or_else(|| dir.to_path_buf());
+        match self {
+            JobCfg::Command(c) => {
+                resolve(&mut c.params.cwd);
+                resolve(&mut c.params.env_file);
+                c.common.set_base(base(&c.params.cwd));
+            }
+            JobCfg::Parallel(p) => {
+                resolve(&mut p.params.cwd);
+                p.common.set_base(base(&p.params.cwd));
+            }
+            JobCfg::Sequential(s) => {
+                resolve(&mut s.params.cwd);
+                s.common.set_base(base(&s.params.cwd));
+            }
+            JobCfg::Shell(s) => {
+                resolve(&mut s.params.cwd);
+                resolve(&mut s.params.env_file);
+                s.common.set_base(base(&s.params.cwd));
+            }
+            JobCfg::Watch(w) => {
+                resolve(&mut w.params.cwd);
+                w.common.set_base(base(&w.params.cwd));
+                w.params.base = Some(base(&w.params.cwd));
+            }
+            #[cfg(test)]
+            JobCfg::Mock(m) => m.common.set_base(dir.to_path_buf()),
+        }
+    }
+
+    /// defaults for jobs of an included cfg file in `dir`
+    ///
+    /// Commands run in `dir` unless specified (`watch_list` is already relative to it).
+    pub fn inherit_dir(&mut self, dir: &Path) {
+        match self {
+            JobCfg::Command(c) => {
+                c.params.cwd.get_or_insert_with(|| dir.to_path_buf());
+            }
+            JobCfg::Shell(s) => {
+                s.params.cwd.get_or_insert_with(|| dir.to_path_buf());
+            }
+            JobCfg::Parallel(_) | JobCfg::Sequential(_) | JobCfg::Watch(_) => {} // children have their own defaults
+            #[cfg(test)]
+            JobCfg::Mock(_) => {}
+        }
+    }
+
     pub fn description(&self) -> String {
         match self {
             JobCfg::Command(c) => c.common.description(),
@@ -18,6 +18,13 @@ pub mod fixtures {
     pub const COMMAND_WITH_ARGS: &str = r#"
 command = "test"
 args = ["test1", "test2"]"#;
+
+    pub const WITH_ENV: &str = r#"
+command = "test"
+env = { KEY = "value" }
+env_file = ".env"
+clear_env = true
+cwd = "backend""#;
 }
 
 #[cfg(test)]
@@ -36,6 +43,15 @@ mod tests {
         let cfg: CommandCfg = toml::from_str(fixtures::COMMAND_WITH_ARGS)?;
         assert_eq!(cfg.params.command, fixtures::COMMAND);
         assert_eq!(cfg.params.args, fixtures::ARGS);
+        assert!(cfg.params.env.is_empty());
+        assert_eq!(cfg.params.env_file, None);
+        assert!(!cfg.params.clear_env);
+
+        let cfg: CommandCfg = toml::from_str(fixtures::WITH_ENV)?;
+        assert_eq!(cfg.params.env.get("KEY"), Some(&"value".to_owned()));
+        assert_eq!(cfg.params.env_file, Some(".env".into()));
+        assert!(cfg.params.clear_env);
+        assert_eq!(cfg.params.cwd, Some("backend".into()));
 
         Ok(())
     }
@@ -1,10 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
 #[derive(serde::Deserialize)]
 pub struct WatchCfg {
-    #[serde(flatten)]
-    pub common: crate::cfg::job_cfg::common::CommonCfg,
+    // before `common` to take `watch_list`, which is also a key of `common`
     #[serde(flatten)]
     pub params: crate::job::modes::WatchParams,
+    #[serde(flatten)]
+    pub common: crate::cfg::job_cfg::common::CommonCfg,
 }
 
 #[cfg(test)]
@@ -29,6 +30,7 @@ mod tests {
 
         assert_eq!(cfg.params.job, fixtures::JOB);
         assert_eq!(cfg.params.watch_list, fixtures::WATCH_LIST);
+        assert!(cfg.common.watch_list().is_empty()); // taken by `params`
         Ok(())
     }
 }
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MPL-2.0
+use serde::Deserialize;
+
+use crate::util::error::{IntoJfError, JfResult};
+
+#[derive(Clone, Copy, Deserialize, Default)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum ParamType {
+    #[default]
+    #[serde(rename = "string")]
+    String,
+    #[serde(rename = "int")]
+    Int,
+    #[serde(rename = "bool")]
+    Bool,
+}
+
+/// scalar value in cfg which is used as string in templates
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ParamValue {
+    String(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl From<ParamValue> for String {
+    fn from(value: ParamValue) -> Self {
+        match value {
+            ParamValue::String(s) => s,
+            ParamValue::Int(i) => i.to_string(),
+            ParamValue::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+fn deserialize_default<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<ParamValue>::deserialize(deserializer)?.map(String::from))
+}
+
+fn deserialize_choices<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<Vec<ParamValue>>::deserialize(deserializer)?
+        .map(|values| values.into_iter().map(String::from).collect()))
+}
+
+/// Definition of a job parameter which is used as `{{name}}`
+#[derive(Clone, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ParamCfg {
+    #[serde(default, rename = "type")]
+    param_type: ParamType,
+    #[serde(default, deserialize_with = "deserialize_default")]
+    default: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_choices")]
+    choices: Option<Vec<String>>,
+}
+
+impl ParamCfg {
+    pub fn default(&self) -> Option<&String> {
+        self.default.as_ref()
+    }
+
+    /// value used to validate job without the param given
+    pub fn placeholder(&self) -> String {
+        if let Some(choice) = self.choices.as_ref().and_then(|c| c.first()) {
+            return choice.clone();
+        }
+        match self.param_type {
+            ParamType::String => String::new(),
+            ParamType::Int => "0".into(),
+            ParamType::Bool => "false".into(),
+        }
+    }
+
+    /// check `value` matches `type` and `choices`
+    pub fn check(&self, name: &str, value: &str) -> JfResult<()> {
+        let type_matched = match self.param_type {
+            ParamType::String => true,
+            ParamType::Int => value.parse::<i64>().is_ok(),
+            ParamType::Bool => value.parse::<bool>().is_ok(),
+        };
+        if !type_matched {
+            return Err(format!(
+                "param `{name}` must be {}, but got `{value}`",
+                self.type_name()
+            )
+            .into_jf_error());
+        }
+        if let Some(choices) = &self.choices {
+            if !choices.iter().any(|c| c == value) {
+                return Err(format!(
+                    "param `{name}` must be one of [{}], but got `{value}`",
+                    choices.join(", ")
+                )
+                .into_jf_error());
+            }
+        }
+        Ok(())
+    }
+
+    fn type_name(&self) -> &str {
+        match self.param_type {
+            ParamType::String => "string",
+            ParamType::Int => "int",
+            ParamType::Bool => "bool",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn deserialize() -> JfResult<()> {
+        let cfg: ParamCfg = toml::from_str(
+            r#"
+default = "debug"
+choices = ["debug", "release"]
+"#,
+        )?;
+        assert_eq!(cfg.param_type, ParamType::String);
+        assert_eq!(cfg.default(), Some(&"debug".to_owned()));
+        assert_eq!(cfg.placeholder(), "debug");
+
+        let cfg: ParamCfg = toml::from_str(
+            r#"
+type = "int"
+default = 3
+"#,
+        )?;
+        assert_eq!(cfg.param_type, ParamType::Int);
+        assert_eq!(cfg.default(), Some(&"3".to_owned()));
+        assert_eq!(cfg.placeholder(), "0");
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn check() {
+        let cfg = ParamCfg {
+            choices: Some(vec!["debug".into(), "release".into()]),
+            ..Default::default()
+        };
+        assert!(cfg.check("target", "release").is_ok());
+        assert!(cfg.check("target", "unknown").is_err());
+
+        let cfg = ParamCfg {
+            param_type: ParamType::Int,
+            ..Default::default()
+        };
+        assert!(cfg.check("jobs", "4").is_ok());
+        assert!(cfg.check("jobs", "four").is_err());
+
+        let cfg = ParamCfg {
+            param_type: ParamType::Bool,
+            ..Default::default()
+        };
+        assert!(cfg.check("fast", "true").is_ok());
+        assert!(cfg.check("fast", "yes").is_err());
+        assert_eq!(cfg.placeholder(), "false");
+    }
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize, Default)]
+#[derive(Clone, Deserialize, Serialize, Default)]
 pub enum Visibility {
     #[serde(rename = "private")]
     Private,
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+use semver::{Op, Version, VersionReq};
+use serde::Deserialize;
+
+use crate::util::error::{IntoJfError, JfResult};
+
+/// `[meta]` section of cfg file
+#[derive(Deserialize, Default)]
+pub struct Meta {
+    /// jf versions the cfg works with (semver requirement like `>=0.2, <0.3`)
+    ///
+    /// Plain version (e.g. `0.1.0` written by `jf --init`) means the minimum version.
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// version of running jf
+pub fn current_version() -> Version {
+    Version::parse(env!("CARGO_PKG_VERSION")).unwrap()
+}
+
+impl Meta {
+    pub fn from_table(table: &toml::Table) -> JfResult<Self> {
+        match table.get("meta") {
+            Some(meta) => Ok(Self::deserialize(meta.clone())?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn requirement(&self) -> JfResult<Option<VersionReq>> {
+        self.version
+            .as_ref()
+            .map(|version| {
+                let req = match Version::parse(version.trim()) {
+                    Ok(_) => format!(">={}", version.trim()),
+                    Err(_) => version.clone(),
+                };
+                VersionReq::parse(&req).map_err(|e| {
+                    format!("Invalid `meta.version` \"{version}\": {e}").into_jf_error()
+                })
+            })
+            .transpose()
+    }
+
+    /// error if `current` jf does not satisfy `version`
+    pub fn check(&self, current: &Version) -> JfResult<()> {
+        match self.requirement()? {
+            Some(req) if !req.matches(current) => Err(format!(
+                "requires jf {req}, but this is jf {current}\nPlease update jf"
+            )
+            .into_jf_error()),
+            _ => Ok(()),
+        }
+    }
+
+    /// version which the cfg is written for (lower bound of `version`)
+    ///
+    /// The oldest version (`0.0.0`) if `version` is not given or has no lower bound (e.g. `<0.3`),
+    /// so every migration runs.
+    pub fn written_for(&self) -> JfResult<Version> {
+        let Some(req) = self.requirement()? else {
+            return Ok(Version::new(0, 0, 0));
+        };
+        Ok(req
+            .comparators
+            .iter()
+            .filter(|c| !matches!(c.op, Op::Less | Op::LessEq))
+            .map(|c| Version::new(c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0)))
+            .max()
+            .unwrap_or(Version::new(0, 0, 0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn meta(version: &str) -> Meta {
+        Meta {
+            version: Some(version.into()),
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn from_table() -> JfResult<()> {
+        let table: toml::Table = "[meta]\nversion = \"0.1.0\"".parse()?;
+        assert_eq!(Meta::from_table(&table)?.version, Some("0.1.0".into()));
+        let table: toml::Table = "[job.test]\ncommand = \"echo\"".parse()?;
+        assert_eq!(Meta::from_table(&table)?.version, None);
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn check() -> JfResult<()> {
+        let current = Version::new(0, 1, 4);
+        assert!(Meta::default().check(&current).is_ok());
+        assert!(meta("0.1.0").check(&current).is_ok()); // same as `>=0.1.0`
+        assert!(meta("0.1.0").check(&Version::new(1, 0, 0)).is_ok());
+        assert!(meta("^0.1.0").check(&Version::new(1, 0, 0)).is_err());
+        assert!(meta(">=0.1, <0.2").check(&current).is_ok());
+        assert!(meta(">=0.2").check(&current).is_err());
+        assert!(meta("0.2.0").check(&current).is_err());
+        assert!(meta("latest").check(&current).is_err());
+        assert!(meta(env!("CARGO_PKG_VERSION"))
+            .check(&current_version())
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn written_for() -> JfResult<()> {
+        assert_eq!(Meta::default().written_for()?, Version::new(0, 0, 0));
+        assert_eq!(meta("0.1.2").written_for()?, Version::new(0, 1, 2));
+        assert_eq!(meta("~0.1").written_for()?, Version::new(0, 1, 0));
+        assert_eq!(meta(">=0.2").written_for()?, Version::new(0, 2, 0));
+        assert_eq!(meta("*").written_for()?, Version::new(0, 0, 0));
+        assert_eq!(meta("<0.3, >=0.2").written_for()?, Version::new(0, 2, 0));
+        assert_eq!(meta("<0.3").written_for()?, Version::new(0, 0, 0));
+        Ok(())
+    }
+}
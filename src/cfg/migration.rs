@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MPL-2.0
+use semver::Version;
+
+use crate::util::error::JfResult;
+
+/// Rewrite of cfg written for older jf, applied before deserializing
+///
+/// Add one when the schema changes (e.g. a key is renamed) so existing cfg files keep loading.
+pub struct Migration {
+    /// version which introduced the change (cfg written for older versions is migrated)
+    pub since: Version,
+    pub migrate: fn(&mut toml::Table) -> JfResult<()>,
+}
+
+/// every migration in ascending order of `since`
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// migrations needed by cfg written for `written_for`
+pub fn pending<'a>(migrations: &'a [Migration], written_for: &Version) -> Vec<&'a Migration> {
+    migrations
+        .iter()
+        .filter(|m| *written_for < m.since)
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// example migration which renames `cmd` of jobs to `command`
+    #[coverage(off)]
+    pub fn rename_cmd(table: &mut toml::Table) -> JfResult<()> {
+        if let Some(toml::Value::Table(jobs)) = table.get_mut("job") {
+            for (_, job) in jobs.iter_mut() {
+                let Some(job) = job.as_table_mut() else {
+                    continue;
+                };
+                if let Some(cmd) = job.remove("cmd") {
+                    job.insert("command".into(), cmd);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn pending_migrations() -> JfResult<()> {
+        let migrations = [Migration {
+            since: Version::new(0, 2, 0),
+            migrate: rename_cmd,
+        }];
+        assert!(pending(&migrations, &Version::new(0, 2, 0)).is_empty());
+        let pending = pending(&migrations, &Version::new(0, 1, 9));
+        assert_eq!(pending.len(), 1);
+
+        let mut table: toml::Table = "[job.test]\ncmd = \"echo\"".parse()?;
+        (pending[0].migrate)(&mut table)?;
+        assert_eq!(
+            table["job"]["test"].get("command"),
+            Some(&toml::Value::String("echo".into()))
+        );
+        Ok(())
+    }
+}
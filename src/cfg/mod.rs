@@ -1,31 +1,186 @@
 // SPDX-License-Identifier: MPL-2.0
 pub mod cfg_path_gen;
 pub mod job_cfg;
+mod meta;
+mod migration;
+pub mod source;
+mod strict;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use serde::Deserialize;
 
-use crate::util::error::JfResult;
+use self::source::{Diagnostic, Location};
+use crate::util::error::{IntoJfError, JfError, JfResult};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct Cfg {
-    #[serde(rename = "job")]
+    /// environment variables inherited by every job (jobs of the file only if included)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// cfg files (glob) whose jobs are added as `<namespace>:<job name>`
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(rename = "job", default)]
     pub jobs: HashMap<String, job_cfg::JobCfg>,
+    /// directory of the cfg file found by searching parent directories
+    #[serde(skip)]
+    root: Option<PathBuf>,
+    /// file defining each job, and the name of the job in it
+    #[serde(skip)]
+    sources: HashMap<String, (Arc<source::Source>, String)>,
+}
+
+/// namespace of jobs in included `path` (directory name for `jf.toml`, otherwise file stem)
+fn namespace(path: &Path) -> String {
+    let name = match path.file_name() {
+        Some(file_name) if file_name == cfg_path_gen::DEFAULT_CFG_NAME => {
+            path.parent().and_then(Path::file_name)
+        }
+        _ => path.file_stem(),
+    };
+    name.map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// error in `text` of `path` pointing at `span`
+fn located(path: &Path, text: &str, span: Option<Range<usize>>, message: String) -> JfError {
+    let location = span.map(|span| Location::new(path, text, span));
+    JfError::Cfg(vec![Diagnostic::error(message, location)])
+}
+
+/// error of toml with its location
+fn toml_error(path: &Path, text: &str, e: toml::de::Error) -> JfError {
+    located(path, text, e.span(), e.message().to_owned())
 }
 
 impl Cfg {
+    /// load `cfg`, or `jf.toml` in the current directory or its parents if not given
     pub fn load(cfg: Option<PathBuf>) -> JfResult<Self> {
-        let file_path = cfg_path_gen::CfgPathGen::new(cfg).gen();
-        match std::fs::read_to_string(file_path) {
-            Ok(c) => Ok(toml::from_str(&c)?),
-            Err(e) => match e.kind() {
-                std::io::ErrorKind::NotFound => Ok(Self {
-                    jobs: HashMap::new(),
-                }),
-                _ => Err(crate::util::error::JfError::IoError(e)),
-            },
+        let searched = cfg.is_none();
+        let file_path = cfg_path_gen::CfgPathGen::new(cfg).find();
+        match Self::load_file(&file_path) {
+            Ok(mut cfg) => {
+                let mut loaded = vec![file_path.canonicalize()?];
+                cfg.load_includes(&file_path, &mut loaded)?;
+                if searched {
+                    cfg.root = file_path.parent().map(Path::to_path_buf);
+                }
+                Ok(cfg)
+            }
+            Err(JfError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// default working directory of jobs (`None` if cfg is given explicitly)
+    pub fn root(&self) -> Option<&PathBuf> {
+        self.root.as_ref()
+    }
+
+    /// file defining each job, and the name of the job in it
+    pub fn sources(&self) -> &HashMap<String, (Arc<source::Source>, String)> {
+        &self.sources
+    }
+
+    fn load_file(file_path: &Path) -> JfResult<Self> {
+        let text = std::fs::read_to_string(file_path)?;
+        let mut cfg =
+            Self::parse(file_path, &text, migration::MIGRATIONS).map_err(|e| match e {
+                JfError::Cfg(_) => e, // already located
+                e => format!("{}: {e}", file_path.display()).into_jf_error(),
+            })?;
+        let source = Arc::new(source::Source::new(file_path, text));
+        cfg.sources = cfg
+            .jobs
+            .keys()
+            .map(|name| (name.clone(), (source.clone(), name.clone())))
+            .collect();
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+        cfg.jobs
+            .values_mut()
+            .for_each(|job_cfg| job_cfg.resolve_paths(dir));
+        Ok(cfg)
+    }
+
+    /// check `[meta] version` and migrate cfg written for older jf before deserializing
+    fn parse(path: &Path, text: &str, migrations: &[migration::Migration]) -> JfResult<Self> {
+        let mut table: toml::Table = text.parse().map_err(|e| toml_error(path, text, e))?;
+        let meta = meta::Meta::from_table(&table)?;
+        meta.check(&meta::current_version())?;
+        let pending = migration::pending(migrations, &meta.written_for()?);
+        for m in &pending {
+            (m.migrate)(&mut table)?;
+        }
+        let mut errors = strict::check(text);
+        if !pending.is_empty() {
+            // keys fixed by migrations are not reported, the rest keep their location in `text`
+            let migrated = strict::check(&table.to_string());
+            errors.retain(|(_, message)| migrated.iter().any(|(_, m)| m == message));
         }
+        if !errors.is_empty() {
+            return Err(JfError::Cfg(
+                errors
+                    .into_iter()
+                    .map(|(span, message)| {
+                        Diagnostic::error(message, Some(Location::new(path, text, span)))
+                    })
+                    .collect(),
+            ));
+        }
+        if pending.is_empty() {
+            return toml::from_str(text).map_err(|e| toml_error(path, text, e)); // keep locations
+        }
+        Ok(Self::deserialize(toml::Value::Table(table))?)
+    }
+
+    /// add jobs of `include` (and their includes) with namespace
+    ///
+    /// `loaded` prevents loading the same file twice (e.g. included by itself).
+    fn load_includes(&mut self, file_path: &Path, loaded: &mut Vec<PathBuf>) -> JfResult<()> {
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+        for pattern in std::mem::take(&mut self.include) {
+            for entry in glob::glob(&dir.join(&pattern).to_string_lossy())? {
+                let path = entry?;
+                let canonical = path.canonicalize()?;
+                if loaded.contains(&canonical) {
+                    continue;
+                }
+                loaded.push(canonical);
+
+                let mut included = Self::load_file(&path)?;
+                let included_dir = path.parent().unwrap_or(Path::new("."));
+                included
+                    .jobs
+                    .values_mut()
+                    .for_each(|job_cfg| job_cfg.inherit_dir(included_dir));
+                included.load_includes(&path, loaded)?; // already namespaced and inherited
+                let namespace = namespace(&path);
+                for (name, mut job_cfg) in included.jobs {
+                    job_cfg.include(&namespace, &included.env);
+                    let name = format!("{namespace}:{name}");
+                    if self.jobs.contains_key(&name) {
+                        return Err(format!(
+                            "job.{name} is defined twice (included from {})",
+                            path.display()
+                        )
+                        .into_jf_error());
+                    }
+                    self.jobs.insert(name, job_cfg);
+                }
+                for (name, source) in included.sources {
+                    self.sources.insert(format!("{namespace}:{name}"), source);
+                }
+            }
+        }
+        Ok(())
     }
 }
 
@@ -36,7 +191,120 @@ mod tests {
     #[test]
     #[coverage(off)]
     fn load() -> JfResult<()> {
-        Cfg::load(Some(cfg_path_gen::tests::fixtures_dir()))?;
+        let cfg = Cfg::load(Some(cfg_path_gen::tests::fixtures_dir()))?;
+        assert_eq!(cfg.env.get("JF_FIXTURE_ENV"), Some(&"fixture".to_owned()));
+        match cfg.jobs.get("pwd-dummy") {
+            Some(job_cfg::JobCfg::Shell(s)) => assert_eq!(
+                s.params.cwd,
+                Some(cfg_path_gen::tests::fixtures_dir().join("../dummy_entities"))
+            ),
+            _ => panic!("job.pwd-dummy is expected to be shell"),
+        }
+        // `watch_list` is relative to `cwd` of the job, or the dir of the cfg file
+        assert_eq!(
+            cfg.jobs.get("pwd-dummy").unwrap().base(),
+            Some(&cfg_path_gen::tests::fixtures_dir().join("../dummy_entities"))
+        );
+        assert_eq!(
+            cfg.jobs.get("exit3").unwrap().base(),
+            Some(&cfg_path_gen::tests::fixtures_dir())
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn load_searched() -> JfResult<()> {
+        let cfg = Cfg::load(None)?; // jf.toml of this repository
+        assert_eq!(cfg.root(), Some(&std::env::current_dir()?));
+        assert!(cfg.jobs.contains_key("ci"));
+        let cfg = Cfg::load(Some(cfg_path_gen::tests::fixtures_dir()))?;
+        assert_eq!(cfg.root(), None);
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn load_include() -> JfResult<()> {
+        let dir = cfg_path_gen::tests::fixtures_dir().join("include");
+        let cfg = Cfg::load(Some(dir.clone()))?;
+        let mut names = cfg.jobs.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["api:ci", "api:env", "api:pwd", "api:test", "ci", "env", "web:ci", "web:test"]
+        );
+        assert_eq!(cfg.env.get("JF_INCLUDE_ENV"), Some(&"root".to_owned()));
+        assert_eq!(cfg.env.get("JF_API_ENV"), None); // only for jobs of api
+        let api_test = cfg.jobs.get("api:test").unwrap();
+        assert_eq!(api_test.namespace(), Some(&"api".to_owned()));
+        assert_eq!(
+            api_test.file_env().get("JF_API_ENV"),
+            Some(&"api".to_owned())
+        );
+        assert_eq!(cfg.jobs.get("ci").unwrap().namespace(), None);
+        match cfg.jobs.get("api:pwd") {
+            Some(job_cfg::JobCfg::Command(c)) => assert_eq!(
+                c.params
+                    .cwd
+                    .as_ref()
+                    .map(|cwd| cwd.canonicalize())
+                    .transpose()?,
+                Some(dir.join("packages/api").canonicalize()?)
+            ),
+            _ => panic!("job.api:pwd is expected to be command"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn namespace() {
+        assert_eq!(super::namespace(Path::new("packages/api/jf.toml")), "api");
+        assert_eq!(super::namespace(Path::new("jobs/lint.toml")), "lint");
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_meta() -> JfResult<()> {
+        let cfg = "[meta]\nversion = \">=0.1\"\n[job.test]\ncommand = \"echo\"";
+        assert_eq!(Cfg::parse(Path::new("jf.toml"), cfg, &[])?.jobs.len(), 1);
+        let cfg = "[meta]\nversion = \">=999\"\n[job.test]\ncommand = \"echo\"";
+        assert!(Cfg::parse(Path::new("jf.toml"), cfg, &[])
+            .err()
+            .is_some_and(|e| e.to_string().contains("requires jf >=999")));
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_migrate() -> JfResult<()> {
+        let migrations = [migration::Migration {
+            since: semver::Version::new(0, 1, 0),
+            migrate: migration::tests::rename_cmd,
+        }];
+        let old = "[meta]\nversion = \"0.0.1\"\n[job.test]\ncmd = \"echo\"";
+        assert!(Cfg::parse(Path::new("jf.toml"), old, &[]).is_err());
+        let cfg = Cfg::parse(Path::new("jf.toml"), old, &migrations)?;
+        assert!(matches!(
+            cfg.jobs.get("test"),
+            Some(job_cfg::JobCfg::Command(_))
+        ));
+
+        // cfg without `[meta]` is the oldest one
+        let old = "[job.test]\ncmd = \"echo\"";
+        let cfg = Cfg::parse(Path::new("jf.toml"), old, &migrations)?;
+        assert!(matches!(
+            cfg.jobs.get("test"),
+            Some(job_cfg::JobCfg::Command(_))
+        ));
+
+        // keys unknown even after migrations are reported
+        let typo = "[job.test]\ncmd = \"echo\"\ndescriptoin = \"test\"";
+        assert!(Cfg::parse(Path::new("jf.toml"), typo, &migrations)
+            .err()
+            .is_some_and(|e| e.to_string().contains("unknown key `descriptoin`")
+                && !e.to_string().contains("`cmd`")));
         Ok(())
     }
 
@@ -0,0 +1,246 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+pub(super) type Table = HashMap<Spanned<String>, Spanned<toml::Value>>;
+
+/// keys of every job with their spans
+#[derive(Deserialize)]
+pub(super) struct Layout {
+    #[serde(default)]
+    pub job: HashMap<Spanned<String>, Table>,
+}
+
+/// Text of a cfg file to point at definitions of jobs
+pub struct Source {
+    path: PathBuf,
+    text: String,
+}
+
+/// Position in a cfg file with the line to show
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Location {
+    path: PathBuf,
+    /// 1-based
+    line: usize,
+    /// 1-based
+    column: usize,
+    line_text: String,
+    width: usize,
+}
+
+impl Location {
+    pub fn new(path: &Path, text: &str, span: Range<usize>) -> Self {
+        let before = &text[..span.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_text = text[line_start..].lines().next().unwrap_or("").to_owned();
+        let column = before[line_start..].chars().count() + 1;
+        let width = text[span.start..span.end.min(line_start + line_text.len())]
+            .chars()
+            .count();
+        Self {
+            path: path.to_path_buf(),
+            line: before.matches('\n').count() + 1,
+            column,
+            line_text,
+            width: width.max(1),
+        }
+    }
+}
+
+/// ```text
+///  --> jf.toml:5:11
+///   |
+/// 5 | command = "carg"
+///   |           ^^^^^^
+/// ```
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(
+            f,
+            "{gutter}--> {}:{}:{}",
+            self.path.display(),
+            self.line,
+            self.column
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Severity {
+    Error,
+    /// reported without making the cfg invalid
+    Warning,
+}
+
+/// Problem in a cfg file with the location to show
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, location: Option<Location>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message,
+            location,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.severity, Severity::Error)
+    }
+
+    /// message followed by the location (without severity)
+    pub fn body(&self) -> String {
+        match &self.location {
+            Some(location) => format!("{}\n{location}", self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// ```text
+/// error: job.build: unknown key `comand`
+/// Did you mean `command`?
+///  --> jf.toml:2:1
+/// ...
+/// ```
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.body())
+    }
+}
+
+impl Source {
+    pub fn new(path: &Path, text: String) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            text,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// location of `key` in job.`job_name` (name in this file)
+    ///
+    /// Points at `item` in the value (e.g. a job in `jobs`) if found, otherwise at the value.
+    /// Without `key` (or if the job does not have it), points at the name of the job.
+    pub fn locate(
+        &self,
+        job_name: &str,
+        key: Option<&str>,
+        item: Option<&str>,
+    ) -> Option<Location> {
+        let layout = toml::from_str::<Layout>(&self.text).ok()?;
+        let (name, table) = layout
+            .job
+            .iter()
+            .find(|(name, _)| name.get_ref() == job_name)?;
+        let value = key.and_then(|key| table.iter().find(|(k, _)| k.get_ref() == key));
+        let span = match value {
+            Some((_, value)) => {
+                let span = value.span();
+                item.and_then(|item| {
+                    let quoted = toml::Value::String(item.to_owned()).to_string();
+                    self.text[span.clone()]
+                        .find(&quoted)
+                        .map(|i| span.start + i..span.start + i + quoted.len())
+                })
+                .unwrap_or(span)
+            }
+            None => name.span(),
+        };
+        Some(Location::new(&self.path, &self.text, span))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"[job.build]
+command = "carg"
+
+[job.ci]
+mode = "parallel"
+jobs = ["build", "tset"]
+"#;
+
+    #[coverage(off)]
+    fn source() -> Source {
+        Source::new(Path::new("jf.toml"), TEXT.into())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn locate() {
+        let source = source();
+        let location = source.locate("build", Some("command"), None).unwrap();
+        assert_eq!((location.line, location.column), (2, 11));
+        assert_eq!(location.width, 6);
+        let location = source.locate("ci", Some("jobs"), Some("tset")).unwrap();
+        assert_eq!((location.line, location.column), (6, 18));
+        let location = source.locate("ci", Some("jobs"), Some("unknown")).unwrap();
+        assert_eq!((location.line, location.column), (6, 8)); // whole value
+        let location = source.locate("ci", Some("finally"), None).unwrap();
+        assert_eq!((location.line, location.column), (4, 6)); // name of the job
+        assert_eq!(source.locate("unknown", None, None), None);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn diagnostic() {
+        let location = source().locate("build", Some("command"), None);
+        let diagnostic = Diagnostic::error("job.build: broken".into(), location);
+        assert!(diagnostic.is_error());
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: job.build: broken\n --> jf.toml:2:11\n  |\n2 | command = \"carg\"\n  |           ^^^^^^"
+        );
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            message: "unused".into(),
+            location: None,
+        };
+        assert!(!diagnostic.is_error());
+        assert_eq!(diagnostic.to_string(), "warning: unused");
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn display() {
+        let location = source().locate("build", Some("command"), None).unwrap();
+        assert_eq!(
+            location.to_string(),
+            " --> jf.toml:2:11\n  |\n2 | command = \"carg\"\n  |           ^^^^^^"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::{collections::HashMap, ops::Range};
+
+use serde::de::IgnoredAny;
+use toml::Spanned;
+
+use super::{
+    job_cfg::{self, JobCfg},
+    source::{Layout, Table},
+};
+use crate::util::suggest::did_you_mean;
+
+/// keys of the top level (`meta` is read before deserializing [`super::Cfg`])
+const TOP_KEYS: &[&str] = &["meta", "env", "include", "job"];
+
+fn unknown_key(key: &Spanned<String>, known: &[&'static str]) -> String {
+    let mut msg = format!("unknown key `{}`", key.get_ref());
+    if let Some(suggestion) = did_you_mean(key.get_ref(), known.iter().copied()) {
+        msg.push_str(&format!("\nDid you mean `{suggestion}`?"));
+    }
+    msg
+}
+
+/// errors of one job with their spans
+fn check_job(job_name: &str, table: &Table) -> Vec<(Range<usize>, String)> {
+    let mode = table.iter().find(|(key, _)| key.get_ref() == "mode");
+    let mode_name = match mode.map(|(_, value)| value.get_ref()) {
+        None => "command".to_owned(),
+        Some(toml::Value::String(mode)) => mode.clone(),
+        Some(other) => other.to_string(),
+    };
+    let Some(known) = JobCfg::keys(&mode_name) else {
+        let span = mode.map(|(_, value)| value.span()).unwrap_or_default();
+        let msg = job_cfg::unknown_mode(&mode_name);
+        return vec![(span, format!("job.{job_name}: {msg}"))];
+    };
+    table
+        .keys()
+        .filter(|key| !known.contains(&key.get_ref().as_str()))
+        .map(|key| {
+            let msg = unknown_key(key, &known);
+            (key.span(), format!("job.{job_name}: {msg}"))
+        })
+        .collect()
+}
+
+/// unknown keys and modes (with suggestion) which serde silently ignores, in order of span
+///
+/// Other errors (e.g. wrong types) are left to the deserialization.
+pub fn check(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut errors = Vec::new();
+    if let Ok(top) = toml::from_str::<HashMap<Spanned<String>, IgnoredAny>>(text) {
+        errors.extend(
+            top.keys()
+                .filter(|key| !TOP_KEYS.contains(&key.get_ref().as_str()))
+                .map(|key| (key.span(), unknown_key(key, TOP_KEYS))),
+        );
+    }
+    if let Ok(layout) = toml::from_str::<Layout>(text) {
+        for (job_name, table) in &layout.job {
+            errors.extend(check_job(job_name.get_ref(), table));
+        }
+    }
+    errors.sort_by_key(|(span, _)| span.start);
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::error::JfResult;
+
+    #[coverage(off)]
+    fn error(text: &str) -> String {
+        check(text)
+            .into_iter()
+            .map(|(_, msg)| msg)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn valid() -> JfResult<()> {
+        assert!(check(&std::fs::read_to_string("tests/fixtures/jf.toml")?).is_empty());
+        assert!(check(&std::fs::read_to_string("jf.toml")?).is_empty());
+        assert!(check(
+            r#"
+[job.live]
+mode = "watch"
+job = "test"
+watch_list = ["src/**"]
+description = "common keys are accepted"
+"#,
+        )
+        .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn unknown_keys() {
+        let text = r#"
+[job.build]
+comand = "cargo"
+
+[job.live]
+mode = "watch"
+job = "build"
+watchlist = ["src/**"]
+"#;
+        assert_eq!(
+            error(text),
+            "job.build: unknown key `comand`\nDid you mean `command`?\n\
+             job.live: unknown key `watchlist`\nDid you mean `watch_list`?"
+        );
+        let spans = check(text)
+            .into_iter()
+            .map(|(span, _)| span)
+            .collect::<Vec<_>>();
+        assert_eq!(&text[spans[0].clone()], "comand");
+        assert_eq!(&text[spans[1].clone()], "watchlist");
+        assert_eq!(
+            error("[job.build]\ncommand = \"cargo\"\nfoo = 1"),
+            "job.build: unknown key `foo`"
+        );
+        assert_eq!(
+            error("includes = []\n"),
+            "unknown key `includes`\nDid you mean `include`?"
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn unknown_mode() {
+        let text = "[job.ci]\nmode = \"paralel\"\njobs = []";
+        assert_eq!(
+            error(text),
+            "job.ci: unknown mode `paralel`\nDid you mean `parallel`?"
+        );
+        assert_eq!(&text[check(text)[0].0.clone()], "\"paralel\"");
+        assert!(error("job.ci = { mode = 1 }").starts_with("job.ci: unknown mode `1`"));
+    }
+}
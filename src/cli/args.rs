@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
-use std::path::PathBuf;
+use std::{collections::HashMap, ffi::OsString, num::NonZeroUsize, path::PathBuf};
 
 use crate::{
     ctx::Ctx,
@@ -8,7 +8,7 @@ use crate::{
 };
 
 use super::models::{
-    action::{Action, Configured, Statics},
+    action::{graph, list, Action, Configured, Format, Statics},
     Opts,
 };
 
@@ -23,6 +23,7 @@ const AUTHOR: &str = "ysuzuki19";
     about,
     long_about = None,
     disable_help_flag = true,
+    group(clap::ArgGroup::new("output").args(["list", "graph"]).multiple(true)),
 )]
 pub struct Args {
     #[arg(long)]
@@ -46,33 +47,133 @@ pub struct Args {
     #[arg(long, default_value = "info")]
     log_level: LogLevel,
 
+    /// Seconds to wait for cancelled jobs to exit before killing them
+    #[arg(long, value_name = "SECONDS", default_value = "5")]
+    grace_period: u64,
+
+    /// Max number of jobs running at once in each parallel job [default: unbounded]
+    #[arg(long, short, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Restart the job on changes of GLOB [default: `watch_list` of the job]
+    ///
+    /// Before the job name, GLOB needs `=` (`--watch=GLOB`), so `jf --watch test` runs the job `test`.
+    /// After it, `jf test --watch GLOB` also works.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        require_equals = true,
+        value_name = "GLOB",
+        action = clap::ArgAction::Append
+    )]
+    watch: Option<Vec<String>>,
+
+    /// Watch files by polling in every watch job (for network filesystems or containers)
+    #[arg(long)]
+    watch_poll: bool,
+
     #[arg(long)]
     completion: Option<clap_complete::Shell>,
 
     #[arg(long)]
     list: bool,
 
+    /// Output format of `--list` (json, yaml, table) or `--graph` (dot, mermaid)
+    #[arg(long, value_name = "FORMAT", requires = "output")]
+    format: Option<Format>,
+
+    /// Include private jobs in `--list`
+    #[arg(long, requires = "list")]
+    all: bool,
+
     #[arg(long)]
     description: bool,
 
+    /// Print jobs referenced by the job as a graph
+    #[arg(long)]
+    graph: bool,
+
     #[command()]
     job_name: Option<String>,
+
+    /// Parameters of the job (`--<name> <value>`)
+    #[arg(allow_hyphen_values = true, value_name = "PARAMS")]
+    job_params: Vec<String>,
+
+    /// Arguments passed to the job (after `--`)
+    #[arg(last = true)]
+    args: Vec<String>,
 }
 
 impl Args {
+    /// parse like `clap::Parser::parse_from`, also accepting options of jf after the job params
+    ///
+    /// e.g. `jf build --target release -v` is parsed as `jf -v build --target release`.
+    pub fn parse_from_cli<I, T>(argv: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        <Self as clap::Parser>::parse_from(hoist_options(argv.into_iter().map(Into::into)))
+    }
+
     pub fn log_level(&self) -> LogLevel {
         self.log_level
     }
 
     pub fn setup(&self, logger: Logger) -> JfResult<(Ctx, Action, Opts)> {
-        let ctx = self.setup_ctx(logger);
+        let ctx = self.setup_ctx(logger)?;
         let action = self.setup_action()?;
         let opts = self.setup_opts();
         Ok((ctx, action, opts))
     }
 
-    fn setup_ctx(&self, logger: Logger) -> Ctx {
-        Ctx::new(logger, "jf", self.verbose)
+    fn setup_ctx(&self, logger: Logger) -> JfResult<Ctx> {
+        let (params, args) = self.split_job_params()?;
+        Ok(Ctx::new(logger, "jf", self.verbose)
+            .with_grace_period(std::time::Duration::from_secs(self.grace_period))
+            .with_max_concurrency(self.jobs.map(NonZeroUsize::get))
+            .with_watch_poll(self.watch_poll)
+            .with_params(params)
+            .with_args(args))
+    }
+
+    /// `--<name> <value>` (or `--<name>=<value>`) after job name, and args after `--`
+    fn split_job_params(&self) -> JfResult<(HashMap<String, String>, Vec<String>)> {
+        // `--` following job params is not consumed by clap
+        let (job_params, rest) = match self.job_params.iter().position(|p| p == "--") {
+            Some(index) => (&self.job_params[..index], &self.job_params[index + 1..]),
+            None => (&self.job_params[..], &[][..]),
+        };
+        let mut params = HashMap::new();
+        let mut iter = job_params.iter();
+        while let Some(param) = iter.next() {
+            let Some(name) = param.strip_prefix("--") else {
+                // e.g. `jf --watch 'src/**' test` takes `src/**` as the job name
+                if self.watch.as_ref().is_some_and(Vec::is_empty) {
+                    return Err(format!(
+                        "Unexpected argument `{param}`\nPlease use `--watch=<GLOB>` before the job name, or `--watch <GLOB>` after it"
+                    )
+                    .into_jf_error());
+                }
+                return Err(format!(
+                    "Unexpected argument `{param}`\nPlease use `-- {param}` to pass it to the job"
+                )
+                .into_jf_error());
+            };
+            let (name, value) = match name.split_once('=') {
+                Some((name, value)) => (name, value.to_owned()),
+                None => (
+                    name,
+                    iter.next()
+                        .ok_or(format!("Missing value of param `{param}`").into_jf_error())?
+                        .clone(),
+                ),
+            };
+            params.insert(name.to_owned(), value);
+        }
+        let args = rest.iter().chain(self.args.iter()).cloned().collect();
+        Ok((params, args))
     }
 
     fn setup_opts(&self) -> Opts {
@@ -91,7 +192,11 @@ impl Args {
         } else if let Some(shell) = self.completion {
             Ok(Statics::Completion(shell).into())
         } else if self.list {
-            Ok(Configured::List.into())
+            Ok(Configured::List {
+                format: self.format.map(list::Format::try_from).transpose()?,
+                all: self.all,
+            }
+            .into())
         } else if self.validate {
             Ok(Configured::Validate.into())
         } else if self.description {
@@ -100,14 +205,116 @@ impl Args {
             } else {
                 Err("Please input <JOB_NAME> to use --description".into_jf_error())
             }
+        } else if self.graph {
+            if let Some(job_name) = self.job_name.clone() {
+                let format = self.format.map(graph::Format::try_from).transpose()?;
+                Ok(Configured::Graph(job_name, format.unwrap_or_default()).into())
+            } else {
+                Err("Please input <JOB_NAME> to use --graph".into_jf_error())
+            }
         } else if let Some(job_name) = self.job_name.clone() {
-            Ok(Configured::Run(job_name).into())
+            match self.watch.clone() {
+                Some(watch_list) => Ok(Configured::Watch(job_name, watch_list).into()),
+                None => Ok(Configured::Run(job_name).into()),
+            }
         } else {
             Ok(Statics::Help.into())
         }
     }
 }
 
+/// How an option of jf takes its value
+#[derive(Clone, Copy, PartialEq)]
+enum Value {
+    None,
+    /// `--name value` or `--name=value`
+    Next,
+    /// `--name=value` only, but `--name value` is accepted after the job name (e.g. `--watch`)
+    Optional,
+}
+
+/// names of options of jf (e.g. `--verbose`, `-v`) and how each takes its value
+fn options() -> Vec<(String, Value)> {
+    let mut command = <Args as clap::CommandFactory>::command();
+    command.build();
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional())
+        .flat_map(|arg| {
+            let value = match (arg.get_action().takes_values(), arg.is_require_equals_set()) {
+                (false, _) => Value::None,
+                (true, false) => Value::Next,
+                (true, true) => Value::Optional,
+            };
+            let long = arg.get_long().map(|long| format!("--{long}"));
+            let short = arg.get_short().map(|short| format!("-{short}"));
+            long.into_iter().chain(short).map(move |name| (name, value))
+        })
+        .collect()
+}
+
+/// move options of jf given after the job name in front of it
+///
+/// Values of job params (`--<name> <value>`) and arguments after `--` are kept as they are.
+fn hoist_options(argv: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+    let options = options();
+    let mut argv = argv.into_iter().peekable();
+    let mut hoisted = argv.next().into_iter().collect::<Vec<_>>(); // binary name
+    let mut rest = Vec::new();
+    let mut has_job_name = false;
+    while let Some(token) = argv.next() {
+        let Some(text) = token.to_str().map(str::to_owned) else {
+            rest.push(token);
+            continue;
+        };
+        if text == "--" {
+            rest.push(token);
+            rest.extend(argv);
+            break;
+        }
+        // `--name=value` and `-jN` have their values attached
+        let (name, has_value) = match text.split_once('=') {
+            Some((name, _)) if text.starts_with("--") => (name, true),
+            _ if !text.starts_with("--") && text.starts_with('-') && text.len() > 2 => {
+                (text.get(..2).unwrap_or(&text), true)
+            }
+            _ => (text.as_str(), false),
+        };
+        match options.iter().find(|(option, _)| option == name) {
+            Some(_) if has_value => hoisted.push(token),
+            Some((_, Value::Next)) => {
+                hoisted.push(token);
+                hoisted.extend(argv.next());
+            }
+            // the job name is given, so the next argument is not taken as it
+            Some((_, Value::Optional))
+                if has_job_name
+                    && argv
+                        .peek()
+                        .and_then(|next| next.to_str())
+                        .is_some_and(|next| !next.starts_with('-')) =>
+            {
+                let mut joined = token;
+                joined.push("=");
+                if let Some(glob) = argv.next() {
+                    joined.push(glob);
+                }
+                hoisted.push(joined);
+            }
+            Some(_) => hoisted.push(token),
+            None => {
+                rest.push(token);
+                if has_job_name && text.starts_with("--") && !has_value {
+                    rest.extend(argv.next()); // value of job param
+                }
+                has_job_name |= !text.starts_with('-');
+            }
+        }
+    }
+    hoisted.extend(rest);
+    hoisted
+}
+
 #[cfg(test)]
 pub mod fixtures {
     pub const APP_NAME: &str = "jf";
@@ -135,10 +342,133 @@ mod tests {
         assert_eq!(args.cfg, None);
         assert_eq!(args.log_level, LogLevel::Info);
         assert_eq!(args.log_level(), LogLevel::Info);
+        assert_eq!(args.grace_period, 5);
+        assert_eq!(args.jobs, None);
         assert_eq!(args.completion, None);
         assert!(!args.list);
+        assert_eq!(args.format, None);
+        assert!(!args.all);
         assert!(!args.description);
+        assert!(!args.graph);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert!(args.args.is_empty());
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_watch() {
+        let args = Args::parse_from([fixtures::APP_NAME, fixtures::JOB_NAME, "--watch=src/**"]);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(args.watch, Some(vec!["src/**".to_string()]));
+
+        let args = Args::parse_from([fixtures::APP_NAME, fixtures::JOB_NAME, "--watch"]);
+        assert_eq!(args.watch, Some(vec![]));
+
+        // the job name is not taken as GLOB
+        let args = Args::parse_from([fixtures::APP_NAME, "--watch", fixtures::JOB_NAME]);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(args.watch, Some(vec![]));
+        let args = Args::parse_from_cli([fixtures::APP_NAME, "--watch", fixtures::JOB_NAME]);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(args.watch, Some(vec![]));
+
+        // GLOB without `=` after the job name
+        let args = Args::parse_from_cli([
+            fixtures::APP_NAME,
+            fixtures::JOB_NAME,
+            "--watch",
+            "src/**",
+            "--watch",
+            "*.toml",
+        ]);
         assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(
+            args.watch,
+            Some(vec!["src/**".to_string(), "*.toml".to_string()])
+        );
+        let args = Args::parse_from_cli([
+            fixtures::APP_NAME,
+            fixtures::JOB_NAME,
+            "--watch",
+            "--target",
+            "release",
+        ]);
+        assert_eq!(args.watch, Some(vec![]));
+        assert_eq!(args.job_params, vec!["--target", "release"]);
+
+        // GLOB before the job name needs `=`
+        let args = Args::parse_from_cli([fixtures::APP_NAME, "--watch", "src/**", "test"]);
+        assert!(args
+            .split_job_params()
+            .err()
+            .is_some_and(|e| e.to_string().contains("--watch=<GLOB>")));
+
+        let args = Args::parse_from([
+            fixtures::APP_NAME,
+            "--watch=src/**",
+            "--watch=Cargo.toml",
+            fixtures::JOB_NAME,
+        ]);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(
+            args.watch,
+            Some(vec!["src/**".to_string(), "Cargo.toml".to_string()])
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_options_after_params() -> JfResult<()> {
+        let parse = |argv: &[&str]| Args::parse_from_cli([&[fixtures::APP_NAME], argv].concat());
+
+        let args = parse(&["build", "--target", "release", "--verbose"]);
+        assert!(args.verbose);
+        assert_eq!(args.job_name, Some("build".to_string()));
+        assert_eq!(args.job_params, vec!["--target", "release"]);
+
+        let args = parse(&["build", "--target", "release", "--watch"]);
+        assert_eq!(args.watch, Some(vec![]));
+        assert_eq!(args.job_params, vec!["--target", "release"]);
+
+        let args = parse(&["build", "--target=release", "-j", "2", "--grace-period=1"]);
+        assert_eq!(args.jobs, NonZeroUsize::new(2));
+        assert_eq!(args.grace_period, 1);
+        assert_eq!(args.job_params, vec!["--target=release"]);
+
+        let args = parse(&[
+            "build",
+            "-j2",
+            "--target",
+            "release",
+            "--log-level",
+            "debug",
+        ]);
+        assert_eq!(args.jobs, NonZeroUsize::new(2));
+        assert_eq!(args.log_level, LogLevel::Debug);
+        assert_eq!(args.job_params, vec!["--target", "release"]);
+
+        // values of job params and args after `--` are not options
+        let args = parse(&["build", "--message", "-v", "--", "--verbose"]);
+        assert!(!args.verbose);
+        let (params, args) = args.split_job_params()?;
+        assert_eq!(
+            params,
+            HashMap::from([("message".to_owned(), "-v".to_owned())])
+        );
+        assert_eq!(args, vec!["--verbose"]);
+
+        let args = parse(&["--cfg", fixtures::CFG_PATH, "build"]);
+        assert_eq!(args.cfg, Some(PathBuf::from(fixtures::CFG_PATH)));
+        assert_eq!(args.job_name, Some("build".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_args() {
+        let args = Args::parse_from([fixtures::APP_NAME, fixtures::JOB_NAME, "--", "-v", "a"]);
+        assert_eq!(args.job_name, Some(fixtures::JOB_NAME.to_string()));
+        assert_eq!(args.args, vec!["-v", "a"]);
     }
 
     #[test]
@@ -151,7 +481,7 @@ mod tests {
 
                 let logging_mock = LoggingMock::new().await;
                 let (ctx, action, opts) = args.setup(logging_mock.logger.clone())?;
-                assert_eq!(ctx, args.setup_ctx(logging_mock.logger));
+                assert_eq!(ctx, args.setup_ctx(logging_mock.logger)?);
                 assert_eq!(action, args.setup_action()?);
                 assert_eq!(opts, args.setup_opts());
                 Ok(())
@@ -161,15 +491,136 @@ mod tests {
 
     #[test]
     #[coverage(off)]
-    fn setup_ctx() {
+    fn setup_ctx() -> JfResult<()> {
         async_test(
             #[coverage(off)]
             async move {
                 let args = Args::parse_from([fixtures::APP_NAME, "--log-level", "error"]);
 
                 let logging_mock = LoggingMock::new().await;
-                let ctx = args.setup_ctx(logging_mock.logger.clone().update(LogLevel::Error));
+                let ctx = args.setup_ctx(logging_mock.logger.clone().update(LogLevel::Error))?;
                 assert_eq!(ctx.logger().level(), LogLevel::Error);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_grace_period() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let args = Args::parse_from([fixtures::APP_NAME, "--grace-period", "1"]);
+
+                let logging_mock = LoggingMock::new().await;
+                let ctx = args.setup_ctx(logging_mock.logger)?;
+                assert_eq!(ctx.grace_period(), std::time::Duration::from_secs(1));
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_jobs() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let logging_mock = LoggingMock::new().await;
+                let args = Args::parse_from([fixtures::APP_NAME, "--jobs", "2"]);
+                let ctx = args.setup_ctx(logging_mock.logger.clone())?;
+                assert_eq!(ctx.max_concurrency(), Some(2));
+
+                let args = Args::parse_from([fixtures::APP_NAME]);
+                let ctx = args.setup_ctx(logging_mock.logger)?;
+                assert_eq!(ctx.max_concurrency(), None);
+
+                assert!(Args::try_parse_from([fixtures::APP_NAME, "-j", "0"]).is_err());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_watch_poll() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let logging_mock = LoggingMock::new().await;
+                let args = Args::parse_from([fixtures::APP_NAME, "--watch-poll"]);
+                assert!(args.setup_ctx(logging_mock.logger.clone())?.watch_poll());
+
+                let args = Args::parse_from([fixtures::APP_NAME]);
+                assert!(!args.setup_ctx(logging_mock.logger)?.watch_poll());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_args() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let args = Args::parse_from([fixtures::APP_NAME, fixtures::JOB_NAME, "--", "a"]);
+
+                let logging_mock = LoggingMock::new().await;
+                let ctx = args.setup_ctx(logging_mock.logger)?;
+                assert_eq!(ctx.args(), ["a"]);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_params() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let args = Args::parse_from([
+                    fixtures::APP_NAME,
+                    fixtures::JOB_NAME,
+                    "--target",
+                    "release",
+                    "--features=a",
+                    "--",
+                    "--nocapture",
+                ]);
+
+                let logging_mock = LoggingMock::new().await;
+                let ctx = args.setup_ctx(logging_mock.logger)?;
+                assert_eq!(
+                    ctx.params(),
+                    &HashMap::from([
+                        ("target".to_owned(), "release".to_owned()),
+                        ("features".to_owned(), "a".to_owned()),
+                    ])
+                );
+                assert_eq!(ctx.args(), ["--nocapture"]);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_ctx_invalid_params() {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let logging_mock = LoggingMock::new().await;
+                for invalid in [["--target"].as_slice(), &["release"]] {
+                    let args = Args::parse_from(
+                        [fixtures::APP_NAME, fixtures::JOB_NAME]
+                            .iter()
+                            .chain(invalid),
+                    );
+                    assert!(args.setup_ctx(logging_mock.logger.clone()).is_err());
+                }
             },
         )
     }
@@ -199,7 +650,33 @@ mod tests {
         let args = Args::parse_from([fixtures::APP_NAME, "--list"]);
 
         let action = args.setup_action()?;
-        assert_eq!(action, Action::Configured(Configured::List));
+        assert_eq!(
+            action,
+            Action::Configured(Configured::List {
+                format: None,
+                all: false
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn setup_action_list_format() -> JfResult<()> {
+        let args = Args::parse_from([fixtures::APP_NAME, "--list", "--format", "json", "--all"]);
+
+        let action = args.setup_action()?;
+        assert_eq!(
+            action,
+            Action::Configured(Configured::List {
+                format: Some(list::Format::Json),
+                all: true
+            })
+        );
+        assert!(Args::try_parse_from([fixtures::APP_NAME, "--format", "json"]).is_err());
+        let args = Args::parse_from([fixtures::APP_NAME, "--list", "--format", "dot"]);
+        assert!(args.setup_action().is_err());
+        assert!(Args::try_parse_from([fixtures::APP_NAME, "--all"]).is_err());
         Ok(())
     }
 
@@ -235,6 +712,49 @@ mod tests {
         assert!(action.is_err());
     }
 
+    #[test]
+    #[coverage(off)]
+    fn setup_action_graph() -> JfResult<()> {
+        let args = Args::parse_from([fixtures::APP_NAME, "--graph", fixtures::JOB_NAME]);
+        assert_eq!(
+            args.setup_action()?,
+            Action::Configured(Configured::Graph(
+                fixtures::JOB_NAME.to_owned(),
+                graph::Format::Dot
+            ))
+        );
+
+        let args = Args::parse_from([
+            fixtures::APP_NAME,
+            "--graph",
+            "--format",
+            "mermaid",
+            fixtures::JOB_NAME,
+        ]);
+        assert_eq!(
+            args.setup_action()?,
+            Action::Configured(Configured::Graph(
+                fixtures::JOB_NAME.to_owned(),
+                graph::Format::Mermaid
+            ))
+        );
+        assert!(Args::parse_from([fixtures::APP_NAME, "--graph"])
+            .setup_action()
+            .is_err());
+        // formats of `--list` are rejected
+        let args = Args::parse_from([
+            fixtures::APP_NAME,
+            "--graph",
+            "--format",
+            "json",
+            fixtures::JOB_NAME,
+        ]);
+        assert!(args.setup_action().err().is_some_and(|e| e
+            .to_string()
+            .contains("--format json is not available for --graph")));
+        Ok(())
+    }
+
     #[test]
     #[coverage(off)]
     fn setup_action_run() -> JfResult<()> {
@@ -248,6 +768,22 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[coverage(off)]
+    fn setup_action_watch() -> JfResult<()> {
+        let args = Args::parse_from([fixtures::APP_NAME, fixtures::JOB_NAME, "--watch=src/**"]);
+
+        let action = args.setup_action()?;
+        assert_eq!(
+            action,
+            Action::Configured(Configured::Watch(
+                fixtures::JOB_NAME.to_owned(),
+                vec!["src/**".to_owned()]
+            ))
+        );
+        Ok(())
+    }
+
     #[test]
     #[coverage(off)]
     fn setup_action_help() -> JfResult<()> {
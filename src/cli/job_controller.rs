@@ -1,18 +1,35 @@
 // SPDX-License-Identifier: MPL-2.0
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
 use crate::{
-    cfg::Cfg,
+    cfg::{
+        source::{Diagnostic, Source},
+        Cfg,
+    },
     ctx::Ctx,
-    job::Runner,
-    jobdef::{Agent, JobdefPool},
+    job::{self, Failure, Job, JoinStatus, Runner},
+    jobdef::{graph::Graph, Agent, JobInfo, JobdefPool},
     util::error::JfResult,
 };
 
+use super::signal_listener::{SignalListener, Signals};
+
+/// time to wait for the job killed by the second signal before exiting anyway
+const KILL_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub struct JobController {
     pool: JobdefPool,
+    env: HashMap<String, String>,
+    sources: HashMap<String, (Arc<Source>, String)>,
 }
 
 impl JobController {
     pub fn new(cfg: Cfg) -> JfResult<Self> {
+        let sources = cfg.sources().clone();
         let job_vec = cfg
             .jobs
             .into_iter()
@@ -20,29 +37,201 @@ impl JobController {
             .collect::<JfResult<_>>()?;
         Ok(Self {
             pool: JobdefPool::new(job_vec),
+            env: cfg.env,
+            sources,
         })
     }
 
-    pub async fn run(&self, ctx: Ctx, job_name: String) -> JfResult<()> {
+    pub async fn run(&self, ctx: Ctx, job_name: String) -> JfResult<JoinStatus> {
+        let ctx = ctx.with_env(self.env.clone());
+        ctx.engine_log("building job runner");
+        let job = self
+            .pool
+            .fresh()
+            .build(ctx.clone(), job_name.clone(), Agent::Cli)?;
+        self.run_job(ctx, job_name, job).await
+    }
+
+    /// run the job wrapped by `watch` (`watch_list` of the job if `watch_list` is empty)
+    pub async fn watch(
+        &self,
+        ctx: Ctx,
+        job_name: String,
+        watch_list: Vec<String>,
+    ) -> JfResult<JoinStatus> {
+        let ctx = ctx.with_env(self.env.clone());
         ctx.engine_log("building job runner");
-        let job = self.pool.build(ctx.clone(), job_name, Agent::Cli)?;
+        let current_dir = std::env::current_dir()?;
+        let watch_list = watch_list // given in cli, so relative to the current directory
+            .into_iter()
+            .map(|item| current_dir.join(item).to_string_lossy().into_owned())
+            .collect();
+        let job =
+            self.pool
+                .fresh()
+                .build_watch(ctx.clone(), job_name.clone(), watch_list, Agent::Cli)?;
+        self.run_job(ctx, job_name, job).await
+    }
+
+    async fn run_job(&self, ctx: Ctx, job_name: String, job: Job) -> JfResult<JoinStatus> {
+        let mut signal_listener = SignalListener::new()?;
 
         ctx.engine_log("starting job runner");
-        job.start().await?.join().await?;
-        Ok(())
+        let job = job.start().await?;
+        let status = supervise(&ctx, &job_name, &job, &mut signal_listener, job::kill_all).await?;
+        if let JoinStatus::Failed(failure) = status {
+            ctx.logger()
+                .error(format!("job.{job_name} failed ({failure})"))
+                .await?;
+        }
+        Ok(status)
     }
 
     pub fn description(&self, job_name: String) -> JfResult<&String> {
         self.pool.description(job_name)
     }
 
-    pub fn list_public(&self) -> Vec<String> {
-        let mut job_names = self.pool.list_public();
-        job_names.sort();
-        job_names
+    /// public job names (and private ones if `all`) grouped by namespace (jobs without namespace first)
+    pub fn list(&self, all: bool) -> Vec<Vec<String>> {
+        let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let job_names = if all {
+            self.pool.list_all()
+        } else {
+            self.pool.list_public()
+        };
+        for job_name in &job_names {
+            let namespace = self.pool.namespace(job_name).unwrap_or_default();
+            groups.entry(namespace).or_default().push(job_name.clone());
+        }
+        groups
+            .into_values()
+            .map(|mut job_names| {
+                job_names.sort();
+                job_names
+            })
+            .collect()
+    }
+
+    pub fn graph(&self, ctx: &Ctx, job_name: String) -> JfResult<Graph> {
+        self.pool.graph(ctx, job_name)
+    }
+
+    /// summary of public jobs (and private ones if `all`) with the cfg file defining each
+    pub fn infos(&self, ctx: &Ctx, all: bool) -> Vec<JobInfo> {
+        let mut infos = self.pool.infos(ctx, all);
+        for info in &mut infos {
+            info.source = self
+                .sources
+                .get(&info.name)
+                .map(|(source, _)| source.path().to_path_buf());
+        }
+        infos
     }
 
-    pub fn validate(&self, ctx: Ctx) -> JfResult<()> {
-        self.pool.validate(ctx)
+    /// one diagnostic with the location in cfg file per problem (empty if every job is valid)
+    pub fn validate(&self, ctx: Ctx) -> Vec<Diagnostic> {
+        let ctx = ctx.with_env(self.env.clone());
+        self.pool
+            .fresh()
+            .diagnose(ctx)
+            .into_iter()
+            .map(|problem| {
+                let location = self
+                    .sources
+                    .get(&problem.job_name)
+                    .and_then(|(source, name)| {
+                        source.locate(name, problem.key, problem.item.as_deref())
+                    });
+                Diagnostic {
+                    severity: problem.severity,
+                    message: problem.message,
+                    location,
+                }
+            })
+            .collect()
+    }
+}
+
+/// join `job`, cancelling it on the first signal and killing every process on the second
+async fn supervise(
+    ctx: &Ctx,
+    job_name: &str,
+    job: &Job,
+    signals: &mut impl Signals,
+    kill_all: fn(),
+) -> JfResult<JoinStatus> {
+    tokio::select! {
+        status = job.join() => status,
+        signal = signals.recv() => {
+            ctx.logger()
+                .warn(format!("signal {signal} received, cancelling job.{job_name} (send again to kill)"))
+                .await?;
+            job.cancel().await?;
+            tokio::select! {
+                status = job.join() => {
+                    status?;
+                }
+                _ = signals.recv() => {
+                    kill_all();
+                    // jf exits even if the job does not finish (e.g. its `join` hangs)
+                    let _ = tokio::time::timeout(KILL_TIMEOUT, job.join()).await;
+                }
+            };
+            Ok(JoinStatus::Failed(Failure::Signal(signal)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        jobdef::Jobdef,
+        util::{error::IntoJfError, testutil::*},
+    };
+
+    use super::*;
+
+    /// signals sent in order, then none
+    struct Queued(Vec<i32>);
+
+    #[async_trait::async_trait]
+    impl Signals for Queued {
+        async fn recv(&mut self) -> i32 {
+            match self.0.pop() {
+                Some(signal) => signal,
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn second_signal_ends_hung_job() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                // SIGTERM is ignored and not killed within the grace period
+                let cfg = "mode = \"shell\"\nscript = \"trap '' TERM; sleep 10\"";
+                let pool = JobdefPool::new(vec![Jobdef::new("hang".into(), toml::from_str(cfg)?)?]);
+                let ctx = Ctx::async_fixture()
+                    .await
+                    .with_grace_period(Duration::from_secs(60));
+                let job = pool.build(ctx.clone(), "hang".into(), Agent::Cli)?;
+                let job = job.start().await?;
+
+                let mut signals = Queued(vec![libc::SIGINT, libc::SIGINT]);
+                let status = tokio::time::timeout(
+                    Duration::from_secs(5),
+                    supervise(&ctx, "hang", &job, &mut signals, || {}), // keep processes of other tests
+                )
+                .await
+                .map_err(|_| "second signal did not end the job".into_jf_error())??;
+                assert_eq!(status, JoinStatus::Failed(Failure::Signal(libc::SIGINT)));
+
+                job.signal(libc::SIGKILL).await?;
+                job.join().await?;
+                Ok(())
+            },
+        )
     }
 }
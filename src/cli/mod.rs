@@ -3,6 +3,7 @@ mod args;
 mod completion_script;
 mod job_controller;
 mod models;
+mod signal_listener;
 
 use crate::{ctx::Ctx, logging::Logger, util::error::JfResult};
 
@@ -24,7 +25,7 @@ impl Cli {
         Ok(Self { ctx, action, opts })
     }
 
-    pub async fn run(self) -> JfResult<()> {
+    pub async fn run(self) -> JfResult<i32> {
         self.action.run(self.ctx, self.opts).await
     }
 }
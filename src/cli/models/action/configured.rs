@@ -3,10 +3,10 @@ use crate::{
     cfg,
     cli::{job_controller, models::Opts},
     ctx::Ctx,
-    util::error::JfResult,
+    util::error::{JfError, JfResult},
 };
 
-use super::{Action, CliAction};
+use super::{graph, list, Action, CliAction};
 
 impl From<Configured> for Action {
     fn from(c: Configured) -> Self {
@@ -17,33 +17,100 @@ impl From<Configured> for Action {
 // Action with job configuration
 #[cfg_attr(test, derive(PartialEq, Debug))]
 pub enum Configured {
-    List,
+    /// list public jobs (and private ones if `all`), only names without `format`
+    List {
+        format: Option<list::Format>,
+        all: bool,
+    },
     Validate,
     Description(String),
+    /// print jobs referenced by the job
+    Graph(String, graph::Format),
     Run(String),
+    /// run and restart the job on changes of the files (`watch_list` of the job if empty)
+    Watch(String, Vec<String>),
 }
 
 #[async_trait::async_trait]
 impl CliAction for Configured {
-    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<()> {
-        let cfg = cfg::Cfg::load(opts.cfg)?;
+    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<i32> {
+        let cfg = match (cfg::Cfg::load(opts.cfg), &self) {
+            (Ok(cfg), _) => cfg,
+            // report like problems of jobs
+            (Err(JfError::Cfg(diagnostics)), Configured::Validate) => {
+                let errors = diagnostics
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                ctx.logger().force(errors.join("\n\n")).await?;
+                ctx.logger()
+                    .force(format!("{} error(s) found", errors.len()))
+                    .await?;
+                return Ok(1);
+            }
+            (Err(e), _) => return Err(e),
+        };
+        let ctx = ctx.inherit_cwd(cfg.root().cloned());
         let jc = job_controller::JobController::new(cfg)?;
         match self {
-            Configured::List => ctx.logger().force(jc.list_public().join(" ")).await?,
-            Configured::Validate => match jc.validate(ctx.clone()) {
-                Ok(_) => ctx.logger().force("All jobs are valid").await?,
-                Err(e) => ctx.logger().force(format!("{e}")).await?,
-            },
-            Configured::Run(name) => jc.run(ctx, name).await?,
+            Configured::List {
+                format: Some(format),
+                all,
+            } => {
+                let output = format.render(&jc.infos(&ctx, all))?;
+                ctx.logger().force(output).await?
+            }
+            Configured::List { format: None, all } => {
+                let groups = jc.list(all).iter().map(|g| g.join(" ")).collect::<Vec<_>>();
+                ctx.logger().force(groups.join("\n")).await?
+            }
+            Configured::Validate => {
+                let diagnostics = jc.validate(ctx.clone());
+                if diagnostics.is_empty() {
+                    ctx.logger().force("All jobs are valid").await?;
+                } else {
+                    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+                    let summary = format!(
+                        "{errors} error(s) and {} warning(s) found",
+                        diagnostics.len() - errors
+                    );
+                    let reports = diagnostics.iter().map(ToString::to_string);
+                    ctx.logger()
+                        .force(reports.collect::<Vec<_>>().join("\n\n"))
+                        .await?;
+                    ctx.logger().force(summary).await?;
+                    if errors > 0 {
+                        return Ok(1);
+                    }
+                }
+            }
+            Configured::Run(name) => return Ok(jc.run(ctx, name).await?.exit_code()),
+            Configured::Watch(name, watch_list) => {
+                return Ok(jc.watch(ctx, name, watch_list).await?.exit_code())
+            }
             Configured::Description(name) => ctx.logger().force(jc.description(name)?).await?,
+            Configured::Graph(name, format) => {
+                let graph = jc.graph(&ctx, name)?;
+                ctx.logger().force(format.render(&graph)).await?
+            }
         }
-        Ok(())
+        Ok(0)
     }
 }
 
 #[cfg(test)]
 mod fixtures {
     pub const JOB_NAME: &str = "test-fixture";
+    pub const EXIT3_JOB_NAME: &str = "exit3";
+    pub const SEQUENTIAL_EXIT3_JOB_NAME: &str = "sequential-exit3";
+    pub const ENV_JOB_NAME: &str = "echo-env";
+    pub const CWD_JOB_NAMES: &[&str] = &["pwd-dummy", "pwd-inherit"];
+    pub const FORWARD_ARGS_JOB_NAME: &str = "forward-args";
+    pub const FORWARD_ARGS: &[&str] = &["--nocapture", "x"];
+    pub const PARAMS_JOB_NAME: &str = "check-target";
+    pub const PARAMS_CALLER_JOB_NAME: &str = "check-release";
+    pub const GO_TEMPLATE_JOB_NAME: &str = "go-template";
+    pub const DEPENDS_ON_JOB_NAME: &str = "dag-ci";
 }
 
 #[cfg(test)]
@@ -55,7 +122,13 @@ mod tests {
     #[test]
     #[coverage(off)]
     fn cover() {
-        println!("{:?}", Configured::List);
+        println!(
+            "{:?}",
+            Configured::List {
+                format: None,
+                all: false
+            }
+        );
     }
 
     #[test]
@@ -64,9 +137,32 @@ mod tests {
         async_test(
             #[coverage(off)]
             async {
-                Configured::List
+                Configured::List {
+                    format: None,
+                    all: false,
+                }
+                .run(Ctx::async_fixture().await, Fixture::fixture())
+                .await?;
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn list_format() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                for format in [list::Format::Json, list::Format::Yaml, list::Format::Table] {
+                    let exit_code = Configured::List {
+                        format: Some(format),
+                        all: true,
+                    }
                     .run(Ctx::async_fixture().await, Fixture::fixture())
                     .await?;
+                    assert_eq!(exit_code, 0);
+                }
                 Ok(())
             },
         )
@@ -86,15 +182,225 @@ mod tests {
         )
     }
 
+    #[test]
+    #[coverage(off)]
+    fn validate_invalid() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                // problems of jobs, unknown keys and warnings only
+                for (dir, expected) in [("invalid", 1), ("typo", 1), ("warning", 0)] {
+                    let cfg = crate::cfg::cfg_path_gen::tests::fixtures_dir().join(dir);
+                    let exit_code = Configured::Validate
+                        .run(Ctx::async_fixture().await, Opts { cfg: Some(cfg) })
+                        .await?;
+                    assert_eq!(exit_code, expected, "{dir}");
+                }
+                Ok(())
+            },
+        )
+    }
+
     #[test]
     #[coverage(off)]
     fn run() -> JfResult<()> {
         async_test(
             #[coverage(off)]
             async {
-                Configured::Run(fixtures::JOB_NAME.to_owned())
+                let exit_code = Configured::Run(fixtures::JOB_NAME.to_owned())
+                    .run(Ctx::async_fixture().await, Fixture::fixture())
+                    .await?;
+                assert_eq!(exit_code, 0);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_included() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let cfg = crate::cfg::cfg_path_gen::tests::fixtures_dir().join("include");
+                for job_name in ["ci", "web:ci", "api:pwd", "env", "api:env"] {
+                    let exit_code = Configured::Run(job_name.to_owned())
+                        .run(
+                            Ctx::async_fixture().await,
+                            Opts {
+                                cfg: Some(cfg.clone()),
+                            },
+                        )
+                        .await?;
+                    assert_eq!(exit_code, 0, "job.{job_name} failed");
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_with_env() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let exit_code = Configured::Run(fixtures::ENV_JOB_NAME.to_owned())
+                    .run(Ctx::async_fixture().await, Fixture::fixture())
+                    .await?;
+                assert_eq!(exit_code, 0);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_with_cwd() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                for job_name in fixtures::CWD_JOB_NAMES {
+                    let exit_code = Configured::Run(job_name.to_string())
+                        .run(Ctx::async_fixture().await, Fixture::fixture())
+                        .await?;
+                    assert_eq!(exit_code, 0, "job.{job_name} failed");
+                }
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_with_args() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let args = fixtures::FORWARD_ARGS
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                let exit_code = Configured::Run(fixtures::FORWARD_ARGS_JOB_NAME.to_owned())
+                    .run(
+                        Ctx::async_fixture().await.with_args(args),
+                        Fixture::fixture(),
+                    )
+                    .await?;
+                assert_eq!(exit_code, 0);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_with_params() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let params = |pairs: &[(&str, &str)]| {
+                    pairs
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect()
+                };
+                let run = |job_name: &str, pairs: &[(&str, &str)]| {
+                    let job_name = job_name.to_owned();
+                    let params = params(pairs);
+                    async move {
+                        Configured::Run(job_name)
+                            .run(
+                                Ctx::async_fixture().await.with_params(params),
+                                Fixture::fixture(),
+                            )
+                            .await
+                    }
+                };
+                assert_eq!(
+                    run(fixtures::PARAMS_JOB_NAME, &[("expected", "debug")]).await?,
+                    0
+                );
+                assert_eq!(
+                    run(
+                        fixtures::PARAMS_JOB_NAME,
+                        &[("target", "release"), ("expected", "release")]
+                    )
+                    .await?,
+                    0
+                );
+                assert_eq!(run(fixtures::PARAMS_CALLER_JOB_NAME, &[]).await?, 0);
+                assert_eq!(
+                    run(fixtures::PARAMS_CALLER_JOB_NAME, &[("target", "debug")]).await?,
+                    1
+                );
+
+                // missing required param, invalid choice and unknown param
+                assert!(run(fixtures::PARAMS_JOB_NAME, &[]).await.is_err());
+                assert!(run(
+                    fixtures::PARAMS_JOB_NAME,
+                    &[("target", "unknown"), ("expected", "unknown")]
+                )
+                .await
+                .is_err());
+                assert!(run(fixtures::PARAMS_CALLER_JOB_NAME, &[("unknown", "x")])
+                    .await
+                    .is_err());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_without_params() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let exit_code = Configured::Run(fixtures::GO_TEMPLATE_JOB_NAME.to_owned())
+                    .run(Ctx::async_fixture().await, Fixture::fixture())
+                    .await?;
+                assert_eq!(exit_code, 0);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_with_depends_on() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                // `dag-build` runs once even if `dag-lint` and `dag-test` depend on it
+                let dir = std::env::temp_dir().join(format!("jf-dag-{}", std::process::id()));
+                std::fs::create_dir_all(&dir)?;
+                let ctx = Ctx::async_fixture().await.inherit_cwd(Some(dir.clone()));
+                let exit_code = Configured::Run(fixtures::DEPENDS_ON_JOB_NAME.to_owned())
+                    .run(ctx, Fixture::fixture())
+                    .await?;
+                assert_eq!(exit_code, 0);
+                std::fs::remove_dir_all(dir)?;
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn run_failed() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let exit_code = Configured::Run(fixtures::EXIT3_JOB_NAME.to_owned())
+                    .run(Ctx::async_fixture().await, Fixture::fixture())
+                    .await?;
+                assert_eq!(exit_code, 3);
+
+                let exit_code = Configured::Run(fixtures::SEQUENTIAL_EXIT3_JOB_NAME.to_owned())
                     .run(Ctx::async_fixture().await, Fixture::fixture())
                     .await?;
+                assert_eq!(exit_code, 3);
                 Ok(())
             },
         )
@@ -113,4 +419,22 @@ mod tests {
             },
         )
     }
+
+    #[test]
+    #[coverage(off)]
+    fn graph() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                for format in [graph::Format::Dot, graph::Format::Mermaid] {
+                    let exit_code =
+                        Configured::Graph(fixtures::DEPENDS_ON_JOB_NAME.to_owned(), format)
+                            .run(Ctx::async_fixture().await, Fixture::fixture())
+                            .await?;
+                    assert_eq!(exit_code, 0);
+                }
+                Ok(())
+            },
+        )
+    }
 }
@@ -0,0 +1,202 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+    jobdef::graph::{Graph, Node},
+    util::error::{IntoJfError, JfError, JfResult},
+};
+
+/// Output format of `jf --graph`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    Mermaid,
+}
+
+impl TryFrom<super::Format> for Format {
+    type Error = JfError;
+
+    fn try_from(format: super::Format) -> JfResult<Self> {
+        match format {
+            super::Format::Dot => Ok(Self::Dot),
+            super::Format::Mermaid => Ok(Self::Mermaid),
+            other => Err(
+                format!("--format {other} is not available for --graph (dot or mermaid)",)
+                    .into_jf_error(),
+            ),
+        }
+    }
+}
+
+impl Format {
+    pub fn render(&self, graph: &Graph) -> String {
+        match self {
+            Self::Dot => render_dot(graph),
+            Self::Mermaid => render_mermaid(graph),
+        }
+    }
+}
+
+/// mode of the job (`?` if decided by params)
+fn mode(node: &Node) -> &str {
+    node.mode.unwrap_or("?")
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", dot_escape(s))
+}
+
+/// private jobs are dashed
+fn render_dot(graph: &Graph) -> String {
+    let mut lines = vec!["digraph jf {".to_owned(), "  node [shape=box];".to_owned()];
+    for node in &graph.nodes {
+        let style = if node.visibility.is_public() {
+            ""
+        } else {
+            ", style=dashed"
+        };
+        lines.push(format!(
+            "  {} [label=\"{}\\n{}\"{style}];",
+            dot_quote(&node.name),
+            dot_escape(&node.name),
+            mode(node)
+        ));
+    }
+    for edge in &graph.edges {
+        let label = edge
+            .label
+            .as_ref()
+            .map(|label| format!(" [label={}]", dot_quote(label)))
+            .unwrap_or_default();
+        lines.push(format!(
+            "  {} -> {}{label};",
+            dot_quote(&edge.from),
+            dot_quote(&edge.to)
+        ));
+    }
+    lines.push("}".to_owned());
+    lines.join("\n")
+}
+
+fn mermaid_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "#quot;"))
+}
+
+/// nodes have ids by order since job names may contain `:`, private jobs are dashed
+fn render_mermaid(graph: &Graph) -> String {
+    let id = |name: &str| {
+        graph
+            .nodes
+            .iter()
+            .position(|node| node.name == name)
+            .map(|i| format!("n{i}"))
+            .unwrap_or_default()
+    };
+    let mut lines = vec!["flowchart TD".to_owned()];
+    for (i, node) in graph.nodes.iter().enumerate() {
+        let class = if node.visibility.is_public() {
+            ""
+        } else {
+            ":::private"
+        };
+        lines.push(format!(
+            "  n{i}[{}]{class}",
+            mermaid_quote(&format!("{}<br/>{}", node.name, mode(node)))
+        ));
+    }
+    for edge in &graph.edges {
+        let arrow = match &edge.label {
+            Some(label) => format!("-->|{}|", mermaid_quote(label)),
+            None => "-->".to_owned(),
+        };
+        lines.push(format!("  {} {arrow} {}", id(&edge.from), id(&edge.to)));
+    }
+    if graph.nodes.iter().any(|node| !node.visibility.is_public()) {
+        lines.push("  classDef private stroke-dasharray: 5 5".to_owned());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cfg::job_cfg::Visibility, jobdef::graph::Edge};
+
+    #[coverage(off)]
+    fn graph() -> Graph {
+        Graph {
+            nodes: vec![
+                Node {
+                    name: "api:ci".into(),
+                    mode: Some("sequential"),
+                    visibility: Visibility::Public,
+                },
+                Node {
+                    name: "lint".into(),
+                    mode: Some("command"),
+                    visibility: Visibility::Private,
+                },
+                Node {
+                    name: "{{job}}".into(),
+                    mode: None,
+                    visibility: Visibility::Public,
+                },
+            ],
+            edges: vec![
+                Edge {
+                    from: "api:ci".into(),
+                    to: "lint".into(),
+                    label: Some("1".into()),
+                },
+                Edge {
+                    from: "api:ci".into(),
+                    to: "{{job}}".into(),
+                    label: Some("finally".into()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn dot() {
+        assert_eq!(
+            Format::Dot.render(&graph()),
+            r#"digraph jf {
+  node [shape=box];
+  "api:ci" [label="api:ci\nsequential"];
+  "lint" [label="lint\ncommand", style=dashed];
+  "{{job}}" [label="{{job}}\n?"];
+  "api:ci" -> "lint" [label="1"];
+  "api:ci" -> "{{job}}" [label="finally"];
+}"#
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn mermaid() {
+        assert_eq!(
+            Format::Mermaid.render(&graph()),
+            r#"flowchart TD
+  n0["api:ci<br/>sequential"]
+  n1["lint<br/>command"]:::private
+  n2["{{job}}<br/>?"]
+  n0 -->|"1"| n1
+  n0 -->|"finally"| n2
+  classDef private stroke-dasharray: 5 5"#
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn quote() {
+        assert_eq!(dot_quote(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(mermaid_quote(r#"a"b"#), "\"a#quot;b\"");
+    }
+}
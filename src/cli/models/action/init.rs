@@ -53,6 +53,7 @@ const TEMPLATE: &str = r#"
 [job.test]
 command = "echo"
 args = ["test"]
+watch_list = ["src/**/*"] # `jf test --watch`
 
 [job.build]
 command = "echo"
@@ -62,16 +63,7 @@ args = ["build"]
 mode = "sequential"
 jobs = ["test", "build"]
 
-[job."test:live"]
-mode = "watch"
-job = "test"
-watch_list = ["src/**/*"]
-
 [job.run]
 command = "echo"
 args = ["run"]
-
-[job."run:live"]
-mode = "watch"
-job = "run"
-watch_list = ["src/**/*"]"#;
+watch_list = ["src/**/*"] # `jf run --watch`"#;
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::{
+    jobdef::JobInfo,
+    util::error::{IntoJfError, JfError, JfResult},
+};
+
+/// Output format of `jf --list --format`
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Format {
+    Json,
+    Yaml,
+    Table,
+}
+
+impl TryFrom<super::Format> for Format {
+    type Error = JfError;
+
+    fn try_from(format: super::Format) -> JfResult<Self> {
+        match format {
+            super::Format::Json => Ok(Self::Json),
+            super::Format::Yaml => Ok(Self::Yaml),
+            super::Format::Table => Ok(Self::Table),
+            other => Err(format!(
+                "--format {other} is not available for --list (json, yaml or table)",
+            )
+            .into_jf_error()),
+        }
+    }
+}
+
+impl Format {
+    pub fn render(&self, infos: &[JobInfo]) -> JfResult<String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(infos)
+                .map_err(|e| format!("Failed to render json: {e}").into_jf_error()),
+            Self::Yaml => serde_norway::to_string(infos)
+                .map(|yaml| yaml.trim_end().to_owned())
+                .map_err(|e| format!("Failed to render yaml: {e}").into_jf_error()),
+            Self::Table => Ok(render_table(infos)),
+        }
+    }
+}
+
+const HEADERS: [&str; 7] = [
+    "NAME",
+    "MODE",
+    "VISIBILITY",
+    "JOBS",
+    "WATCH_LIST",
+    "SOURCE",
+    "DESCRIPTION",
+];
+
+fn render_table(infos: &[JobInfo]) -> String {
+    let rows = infos
+        .iter()
+        .map(|info| {
+            [
+                info.name.clone(),
+                info.mode.to_owned(),
+                if info.visibility.is_public() {
+                    "public".to_owned()
+                } else {
+                    "private".to_owned()
+                },
+                info.jobs.join(","),
+                info.watch_list.join(","),
+                info.source
+                    .as_ref()
+                    .map(|s| s.display().to_string())
+                    .unwrap_or_default(),
+                info.description.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut widths = HEADERS.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    std::iter::once(HEADERS.map(str::to_owned))
+        .chain(rows)
+        .map(|row| {
+            row.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::cfg::job_cfg::Visibility;
+
+    #[coverage(off)]
+    fn infos() -> Vec<JobInfo> {
+        vec![
+            JobInfo {
+                name: "ci".into(),
+                mode: "sequential",
+                description: "run every check".into(),
+                visibility: Visibility::Public,
+                jobs: vec!["lint".into(), "test".into()],
+                watch_list: vec![],
+                source: Some(PathBuf::from("jf.toml")),
+            },
+            JobInfo {
+                name: "lint".into(),
+                mode: "command",
+                description: String::new(),
+                visibility: Visibility::Private,
+                jobs: vec![],
+                watch_list: vec!["src/**".into()],
+                source: None,
+            },
+        ]
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn json() -> JfResult<()> {
+        let json = Format::Json.render(&infos())?;
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["name"], "ci");
+        assert_eq!(value[0]["jobs"][1], "test");
+        assert_eq!(value[0]["source"], "jf.toml");
+        assert_eq!(value[1]["visibility"], "private");
+        assert_eq!(value[1]["source"], serde_json::Value::Null);
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn yaml() -> JfResult<()> {
+        let yaml = Format::Yaml.render(&infos())?;
+        assert!(yaml.starts_with("- name: ci\n  mode: sequential\n"));
+        assert!(yaml.contains("  visibility: private\n"));
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn table() -> JfResult<()> {
+        assert_eq!(
+            Format::Table.render(&infos())?,
+            "NAME  MODE        VISIBILITY  JOBS       WATCH_LIST  SOURCE   DESCRIPTION\n\
+             ci    sequential  public      lint,test              jf.toml  run every check\n\
+             lint  command     private                src/**"
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: MPL-2.0
 mod configured;
+pub mod graph;
 pub mod init;
+pub mod list;
 mod statics;
 
 use crate::ctx::Ctx;
@@ -11,9 +13,34 @@ pub use self::statics::Statics;
 
 use super::Opts;
 
+/// Output format of `--list` and `--graph` (`--format`)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// `--list`
+    Json,
+    /// `--list`
+    Yaml,
+    /// `--list`
+    Table,
+    /// `--graph` (Graphviz DOT, default)
+    Dot,
+    /// `--graph`
+    Mermaid,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match clap::ValueEnum::to_possible_value(self) {
+            Some(value) => f.write_str(value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait CliAction {
-    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<()>;
+    /// run action and return exit code of `jf`
+    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<i32>;
 }
 
 #[cfg_attr(test, derive(PartialEq, Debug))]
@@ -24,7 +51,7 @@ pub enum Action {
 
 #[async_trait::async_trait]
 impl CliAction for Action {
-    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<()> {
+    async fn run(self, ctx: Ctx, opts: Opts) -> JfResult<i32> {
         match self {
             Action::Statics(s) => s.run(ctx, opts).await,
             Action::Configured(c) => c.run(ctx, opts).await,
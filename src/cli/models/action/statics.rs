@@ -24,7 +24,7 @@ impl From<Statics> for Action {
 
 #[async_trait::async_trait]
 impl CliAction for Statics {
-    async fn run(self, ctx: Ctx, _: Opts) -> JfResult<()> {
+    async fn run(self, ctx: Ctx, _: Opts) -> JfResult<i32> {
         let mut cmd = <Args as clap::CommandFactory>::command();
         let s = match self {
             Statics::Completion(shell) => completion_script::generate(shell),
@@ -33,7 +33,7 @@ impl CliAction for Statics {
             Statics::Init(mode) => mode.render().await,
         };
         ctx.logger().force(s).await?;
-        Ok(())
+        Ok(0)
     }
 }
 
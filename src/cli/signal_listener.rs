@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MPL-2.0
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+use crate::util::error::JfResult;
+
+/// Source of signals sent to jf
+#[async_trait::async_trait]
+pub trait Signals: Send {
+    /// wait for the next signal and return its number
+    async fn recv(&mut self) -> i32;
+}
+
+/// Listener of SIGINT and SIGTERM sent to jf
+pub struct SignalListener {
+    interrupt: Signal,
+    terminate: Signal,
+}
+
+impl SignalListener {
+    pub fn new() -> JfResult<Self> {
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Signals for SignalListener {
+    async fn recv(&mut self) -> i32 {
+        tokio::select! {
+            _ = self.interrupt.recv() => libc::SIGINT,
+            _ = self.terminate.recv() => libc::SIGTERM,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[coverage(off)]
+    async fn new() {
+        // signals are not raised here because every listener in the test process would receive them
+        assert!(SignalListener::new().is_ok());
+    }
+}
@@ -2,6 +2,8 @@
 mod app_stack;
 mod scopeout_log;
 
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
 use crate::{
     ctx::{app_stack::AppStack, scopeout_log::ScopeoutLog},
     logging::{log_generator, LogLevel, Logger},
@@ -13,8 +15,23 @@ pub struct Ctx {
     logger: Logger,
     app_stack: AppStack,
     verbose: bool,
+    grace_period: Duration,
+    env: Arc<HashMap<String, String>>,
+    file_env: Arc<HashMap<String, String>>,
+    cwd: Option<PathBuf>,
+    args: Arc<Vec<String>>,
+    params: Arc<HashMap<String, String>>,
+    job_path: Vec<String>,
+    max_concurrency: Option<usize>,
+    changed_files: Arc<Vec<String>>,
+    watch_poll: bool,
 }
 
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// environment variable of files changed before `watch` restarted the job (one per line)
+pub const CHANGED_FILES_ENV: &str = "JF_CHANGED_FILES";
+
 // pub struct CtxSpanGuard<'a> {
 //     ctx: &'a mut Ctx,
 // }
@@ -37,6 +54,16 @@ impl Ctx {
             logger,
             app_stack: AppStack::new(name),
             verbose,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            env: Default::default(),
+            file_env: Default::default(),
+            cwd: None,
+            args: Default::default(),
+            params: Default::default(),
+            job_path: Vec::new(),
+            max_concurrency: None,
+            changed_files: Default::default(),
+            watch_poll: false,
         }
     }
 
@@ -44,6 +71,127 @@ impl Ctx {
         self.logger.clone()
     }
 
+    /// time to wait after SIGTERM before killing cancelled processes
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// environment variables inherited by every job (`[env]` in cfg)
+    pub fn env(&self) -> &HashMap<String, String> {
+        &self.env
+    }
+
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = Arc::new(env);
+        self
+    }
+
+    /// `[env]` of the included cfg file defining the current job (overridden by `env`)
+    pub fn file_env(&self) -> &HashMap<String, String> {
+        &self.file_env
+    }
+
+    pub fn with_file_env(mut self, file_env: HashMap<String, String>) -> Self {
+        self.file_env = Arc::new(file_env);
+        self
+    }
+
+    /// default working directory of jobs without `cwd`
+    pub fn cwd(&self) -> Option<&PathBuf> {
+        self.cwd.as_ref()
+    }
+
+    /// override default working directory if `cwd` is given
+    pub fn inherit_cwd(mut self, cwd: Option<PathBuf>) -> Self {
+        if cwd.is_some() {
+            self.cwd = cwd;
+        }
+        self
+    }
+
+    /// extra arguments passed after `--` in cli
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = Arc::new(args);
+        self
+    }
+
+    /// ctx for child jobs which are not designated to receive args
+    pub fn without_args(&self) -> Self {
+        let mut cloned = self.clone();
+        cloned.args = Default::default();
+        cloned
+    }
+
+    /// values of job params (`{{name}}` in templates)
+    pub fn params(&self) -> &HashMap<String, String> {
+        &self.params
+    }
+
+    pub fn with_params(mut self, params: HashMap<String, String>) -> Self {
+        self.params = Arc::new(params);
+        self
+    }
+
+    /// ctx for child jobs which have their own params
+    pub fn without_params(&self) -> Self {
+        let mut cloned = self.clone();
+        cloned.params = Default::default();
+        cloned
+    }
+
+    /// names of jobs being built from the root job to the current one
+    pub fn job_path(&self) -> &[String] {
+        &self.job_path
+    }
+
+    pub fn push_job(mut self, job_name: &str) -> Self {
+        self.job_path.push(job_name.to_owned());
+        self
+    }
+
+    /// max number of running jobs in each `parallel` without `max_concurrency` (unbounded if `None`)
+    pub fn max_concurrency(&self) -> Option<usize> {
+        self.max_concurrency
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: Option<usize>) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    /// files changed before `watch` restarted the job
+    pub fn changed_files(&self) -> &[String] {
+        &self.changed_files
+    }
+
+    /// also exported to commands as `JF_CHANGED_FILES`
+    pub fn with_changed_files(mut self, changed_files: Vec<String>) -> Self {
+        let mut env = (*self.env).clone();
+        env.insert(CHANGED_FILES_ENV.to_owned(), changed_files.join("\n"));
+        self.env = Arc::new(env);
+        self.changed_files = Arc::new(changed_files);
+        self
+    }
+
+    /// force every `watch` to use the polling backend
+    pub fn watch_poll(&self) -> bool {
+        self.watch_poll
+    }
+
+    pub fn with_watch_poll(mut self, watch_poll: bool) -> Self {
+        self.watch_poll = watch_poll;
+        self
+    }
+
     // pub fn with_span(&mut self, span: &str) -> CtxSpanGuard<'_> {
     //     self.app_stack.push(span);
     //     CtxSpanGuard::new(self)
@@ -91,6 +239,16 @@ mod tests {
                 logger: logging_mock.logger,
                 app_stack: AppStack::new("test"),
                 verbose: false,
+                grace_period: DEFAULT_GRACE_PERIOD,
+                env: Default::default(),
+                file_env: Default::default(),
+                cwd: None,
+                args: Default::default(),
+                params: Default::default(),
+                job_path: Vec::new(),
+                max_concurrency: None,
+                changed_files: Default::default(),
+                watch_poll: false,
             }
         }
     }
@@ -106,4 +264,19 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    #[coverage(off)]
+    fn with_changed_files() {
+        async_test(
+            #[coverage(off)]
+            async {
+                let ctx = Ctx::async_fixture()
+                    .await
+                    .with_changed_files(vec!["src/a.rs".into(), "src/b.rs".into()]);
+                assert_eq!(ctx.changed_files(), ["src/a.rs", "src/b.rs"]);
+                assert_eq!(ctx.env()[CHANGED_FILES_ENV], "src/a.rs\nsrc/b.rs");
+            },
+        );
+    }
 }
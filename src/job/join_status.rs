@@ -1,16 +1,128 @@
 // SPDX-License-Identifier: MPL-2.0
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
 pub enum JoinStatus {
     Succeed,
-    Failed,
+    Failed(Failure),
+}
+
+/// Reason why a job failed
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum Failure {
+    /// exited with non-zero exit code
+    Code(i32),
+    /// terminated by signal
+    Signal(i32),
+    /// cancelled before finishing by itself
+    Cancelled,
+    /// cancelled because `timeout` elapsed
+    TimedOut,
 }
 
 impl JoinStatus {
-    #[cfg(test)]
     pub fn is_succeed(&self) -> bool {
         matches!(self, Self::Succeed)
     }
 
     pub fn is_failed(&self) -> bool {
-        matches!(self, Self::Failed)
+        matches!(self, Self::Failed(_))
+    }
+
+    /// process exit code for `jf` itself
+    ///
+    /// - `0` on success
+    /// - the child's exit code when it exited with non-zero
+    /// - `128 + N` when the child was killed by signal `N`
+    /// - `130` when the job was cancelled
+    /// - `124` when the job timed out
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Succeed => 0,
+            Self::Failed(failure) => failure.exit_code(),
+        }
+    }
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Code(code) => *code,
+            Self::Signal(signal) => 128 + signal,
+            Self::Cancelled => 130,
+            Self::TimedOut => 124,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Code(code) => write!(f, "exit code {code}"),
+            Self::Signal(signal) => write!(f, "killed by signal {signal}"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+impl From<std::process::ExitStatus> for JoinStatus {
+    fn from(status: std::process::ExitStatus) -> Self {
+        if status.success() {
+            return Self::Succeed;
+        }
+        if let Some(code) = status.code() {
+            return Self::Failed(Failure::Code(code));
+        }
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Self::Failed(Failure::Signal(signal));
+        }
+        Self::Failed(Failure::Cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn exit_code() {
+        assert_eq!(JoinStatus::Succeed.exit_code(), 0);
+        assert_eq!(JoinStatus::Failed(Failure::Code(3)).exit_code(), 3);
+        assert_eq!(JoinStatus::Failed(Failure::Signal(9)).exit_code(), 137);
+        assert_eq!(JoinStatus::Failed(Failure::Cancelled).exit_code(), 130);
+        assert_eq!(JoinStatus::Failed(Failure::TimedOut).exit_code(), 124);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn from_exit_status() {
+        let status = std::process::ExitStatus::from_raw(0);
+        assert_eq!(JoinStatus::from(status), JoinStatus::Succeed);
+
+        let status = std::process::ExitStatus::from_raw(2 << 8);
+        assert_eq!(
+            JoinStatus::from(status),
+            JoinStatus::Failed(Failure::Code(2))
+        );
+
+        let status = std::process::ExitStatus::from_raw(9);
+        assert_eq!(
+            JoinStatus::from(status),
+            JoinStatus::Failed(Failure::Signal(9))
+        );
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn display() {
+        assert_eq!(Failure::Code(1).to_string(), "exit code 1");
+        assert_eq!(Failure::Signal(15).to_string(), "killed by signal 15");
+        assert_eq!(Failure::Cancelled.to_string(), "cancelled");
+        assert_eq!(Failure::TimedOut.to_string(), "timed out");
     }
 }
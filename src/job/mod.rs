@@ -4,34 +4,65 @@ mod finish_notify;
 mod join_status;
 pub mod modes;
 mod runner;
+mod task_handle;
 #[cfg(test)]
 mod tests;
 
 use futures::{stream, StreamExt};
 
+use self::canceller::Canceller;
+pub use self::join_status::{Failure, JoinStatus};
+pub use self::modes::{kill_all, SharedJob};
 pub use self::runner::*;
-use self::{canceller::Canceller, join_status::JoinStatus};
 use crate::{cfg::job_cfg::JobCfg, ctx::Ctx, jobdef::JobdefPool, util::error::JfResult};
 
 #[derive(Clone)]
 pub enum Job {
     Command(modes::Command),
+    Depends(modes::Depends),
     Parallel(modes::Parallel),
+    Retry(modes::Retry),
     Sequential(modes::Sequential),
     Shell(modes::Shell),
+    Timeout(modes::Timeout),
     Watch(modes::Watch),
     #[cfg(test)]
     Mock(modes::Mock),
 }
 
 impl Job {
+    /// watch `params.watch_list` to restart `params.job`
+    pub fn watch(ctx: Ctx, params: modes::WatchParams, pool: JobdefPool) -> JfResult<Self> {
+        Ok(modes::Watch::new(ctx, params, pool)?.into())
+    }
+
+    /// wrap job to be cancelled after `duration`
+    pub fn timeout(self, ctx: Ctx, duration: std::time::Duration) -> Self {
+        modes::Timeout::new(ctx, duration, self).into()
+    }
+
+    /// wrap job to rerun after it failed
+    pub fn retry(self, ctx: Ctx, params: modes::RetryParams) -> Self {
+        modes::Retry::new(ctx, params, self).into()
+    }
+
+    /// wrap job to run after `deps` succeeded
+    pub fn depends_on(self, ctx: Ctx, deps: Vec<SharedJob>) -> Self {
+        modes::Depends::new(ctx, deps, self).into()
+    }
+
+    /// build job from `job_cfg` with `{{name}}` substituted by `ctx.params()`
     pub fn new(ctx: Ctx, job_cfg: &JobCfg, pool: JobdefPool) -> JfResult<Self> {
+        let vars = ctx.params().clone();
+        let ctx = ctx.without_params();
         Ok(match job_cfg {
-            JobCfg::Command(c) => modes::Command::new(ctx, c.params.clone()).into(),
-            JobCfg::Parallel(c) => modes::Parallel::new(ctx, c.params.clone(), pool)?.into(),
-            JobCfg::Sequential(c) => modes::Sequential::new(ctx, c.params.clone(), pool)?.into(),
-            JobCfg::Shell(c) => modes::Shell::new(ctx, c.params.clone()).into(),
-            JobCfg::Watch(c) => modes::Watch::new(ctx, c.params.clone(), pool)?.into(),
+            JobCfg::Command(c) => modes::Command::new(ctx, c.params.render(&vars)?).into(),
+            JobCfg::Parallel(c) => modes::Parallel::new(ctx, c.params.render(&vars)?, pool)?.into(),
+            JobCfg::Sequential(c) => {
+                modes::Sequential::new(ctx, c.params.render(&vars)?, pool)?.into()
+            }
+            JobCfg::Shell(c) => modes::Shell::new(ctx, c.params.render(&vars)?).into(),
+            JobCfg::Watch(c) => modes::Watch::new(ctx, c.params.render(&vars)?, pool)?.into(),
             #[cfg(test)]
             JobCfg::Mock(c) => modes::Mock::new(c.params.clone()).into(),
         })
@@ -43,9 +74,12 @@ impl Bunshin for Job {
     async fn bunshin(&self) -> Self {
         match self {
             Self::Command(t) => Self::Command(t.bunshin().await),
+            Self::Depends(t) => Self::Depends(t.bunshin().await),
             Self::Parallel(t) => Self::Parallel(t.bunshin().await),
+            Self::Retry(t) => Self::Retry(t.bunshin().await),
             Self::Sequential(t) => Self::Sequential(t.bunshin().await),
             Self::Shell(t) => Self::Shell(t.bunshin().await),
+            Self::Timeout(t) => Self::Timeout(t.bunshin().await),
             Self::Watch(t) => Self::Watch(t.bunshin().await),
             #[cfg(test)]
             Self::Mock(t) => Self::Mock(t.bunshin().await),
@@ -58,9 +92,12 @@ impl Checker for Job {
     async fn is_finished(&self) -> JfResult<bool> {
         match self {
             Self::Command(t) => t.is_finished().await,
+            Self::Depends(t) => t.is_finished().await,
             Self::Parallel(t) => t.is_finished().await,
+            Self::Retry(t) => t.is_finished().await,
             Self::Sequential(t) => t.is_finished().await,
             Self::Shell(t) => t.is_finished().await,
+            Self::Timeout(t) => t.is_finished().await,
             Self::Watch(t) => t.is_finished().await,
             #[cfg(test)]
             Self::Mock(t) => t.is_finished().await,
@@ -73,9 +110,12 @@ impl Runner for Job {
     async fn start(&self) -> JfResult<Self> {
         Ok(match self {
             Self::Command(t) => t.start().await?.into(),
+            Self::Depends(t) => t.start().await?.into(),
             Self::Parallel(t) => t.start().await?.into(),
+            Self::Retry(t) => t.start().await?.into(),
             Self::Sequential(t) => t.start().await?.into(),
             Self::Shell(t) => t.start().await?.into(),
+            Self::Timeout(t) => t.start().await?.into(),
             Self::Watch(t) => t.start().await?.into(),
             #[cfg(test)]
             Self::Mock(t) => t.start().await?.into(),
@@ -85,9 +125,12 @@ impl Runner for Job {
     async fn cancel(&self) -> JfResult<Self> {
         Ok(match self {
             Self::Command(t) => t.cancel().await?.into(),
+            Self::Depends(t) => t.cancel().await?.into(),
             Self::Parallel(t) => t.cancel().await?.into(),
+            Self::Retry(t) => t.cancel().await?.into(),
             Self::Sequential(t) => t.cancel().await?.into(),
             Self::Shell(t) => t.cancel().await?.into(),
+            Self::Timeout(t) => t.cancel().await?.into(),
             Self::Watch(t) => t.cancel().await?.into(),
             #[cfg(test)]
             Self::Mock(t) => t.cancel().await?.into(),
@@ -97,21 +140,42 @@ impl Runner for Job {
     async fn join(&self) -> JfResult<JoinStatus> {
         match self {
             Self::Command(t) => t.join().await,
+            Self::Depends(t) => t.join().await,
             Self::Parallel(t) => t.join().await,
+            Self::Retry(t) => t.join().await,
             Self::Sequential(t) => t.join().await,
             Self::Shell(t) => t.join().await,
+            Self::Timeout(t) => t.join().await,
             Self::Watch(t) => t.join().await,
             #[cfg(test)]
             Self::Mock(t) => t.join().await,
         }
     }
 
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        match self {
+            Self::Command(t) => t.signal(signal).await,
+            Self::Depends(t) => t.signal(signal).await,
+            Self::Parallel(t) => t.signal(signal).await,
+            Self::Retry(t) => t.signal(signal).await,
+            Self::Sequential(t) => t.signal(signal).await,
+            Self::Shell(t) => t.signal(signal).await,
+            Self::Timeout(t) => t.signal(signal).await,
+            Self::Watch(t) => t.signal(signal).await,
+            #[cfg(test)]
+            Self::Mock(t) => t.signal(signal).await,
+        }
+    }
+
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
         match self {
             Self::Command(t) => Self::Command(t.set_canceller(canceller)),
+            Self::Depends(t) => Self::Depends(t.set_canceller(canceller)),
             Self::Parallel(t) => Self::Parallel(t.set_canceller(canceller)),
+            Self::Retry(t) => Self::Retry(t.set_canceller(canceller)),
             Self::Sequential(t) => Self::Sequential(t.set_canceller(canceller)),
             Self::Shell(t) => Self::Shell(t.set_canceller(canceller)),
+            Self::Timeout(t) => Self::Timeout(t.set_canceller(canceller)),
             Self::Watch(t) => Self::Watch(t.set_canceller(canceller)),
             #[cfg(test)]
             Self::Mock(t) => Self::Mock(t.set_canceller(canceller)),
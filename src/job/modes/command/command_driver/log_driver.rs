@@ -4,7 +4,10 @@ use tokio_stream::StreamExt;
 
 use crate::{
     ctx::Ctx,
-    job::{join_status::JoinStatus, JfHandle},
+    job::{
+        join_status::{Failure, JoinStatus},
+        JfHandle,
+    },
     util::error::{IntoJfError, JfResult},
 };
 
@@ -70,9 +73,12 @@ impl LogDriver {
 
     pub async fn join(&mut self) -> JfResult<JoinStatus> {
         if let Some(handle) = self.handle.take() {
-            handle.await?
+            let status = handle.await?;
+            // output of the job is written before it is reported as finished
+            self.ctx.logger().flush().await?;
+            status
         } else {
-            Ok(JoinStatus::Failed)
+            Ok(JoinStatus::Failed(Failure::Cancelled))
         }
     }
 }
@@ -1,6 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 mod log_driver;
+mod process_group;
 
+pub use process_group::{kill_all, ProcessGroup};
+
+use super::CommandParams;
 use crate::{
     ctx::Ctx,
     job::join_status::JoinStatus,
@@ -10,56 +14,90 @@ use crate::{
 pub struct CommandDriver {
     child: tokio::process::Child,
     log_driver: log_driver::LogDriver,
+    process_group: ProcessGroup,
 }
 
 impl CommandDriver {
-    pub async fn spawn(ctx: Ctx, command: &String, args: &Vec<String>) -> JfResult<Self> {
+    pub async fn spawn(ctx: Ctx, params: &CommandParams) -> JfResult<Self> {
+        let command = &params.command;
         let mut cmd = tokio::process::Command::new(command);
-        cmd.args(args);
+        cmd.args(&params.args);
+        if params.clear_env {
+            cmd.env_clear();
+        }
+        cmd.envs(ctx.file_env());
+        cmd.envs(ctx.env());
+        if let Some(env_file) = &params.env_file {
+            cmd.envs(super::env_file::load(env_file)?);
+        }
+        cmd.envs(&params.env);
+        if let Some(cwd) = params.cwd.as_ref().or(ctx.cwd()) {
+            cmd.current_dir(cwd);
+        }
         cmd.stdout(std::process::Stdio::piped());
         cmd.stderr(std::process::Stdio::piped());
+        cmd.process_group(0); // to signal grandchildren together
 
         let mut child = cmd.spawn()?;
+        let process_group = match child.id() {
+            Some(pid) => ProcessGroup::register(pid),
+            None => return Err(format!("Failed to get pid of {command}").into_jf_error()),
+        };
         let mut log_driver = log_driver::LogDriver::new(ctx);
 
         match log_driver.mount(child.stdout.take(), child.stderr.take()) {
-            Ok(_) => Ok(Self { child, log_driver }),
+            Ok(_) => Ok(Self {
+                child,
+                log_driver,
+                process_group,
+            }),
             Err(_) => {
-                child.kill().await?;
+                process_group.kill()?;
+                child.wait().await?;
+                process_group.unregister();
                 Err(format!("Failed to handle stdout for {0}", command.to_owned()).into_jf_error())
             }
         }
     }
 
+    pub fn process_group(&self) -> ProcessGroup {
+        self.process_group
+    }
+
     #[allow(dead_code)]
     pub async fn is_finished(&mut self) -> JfResult<bool> {
         Ok(self.child.try_wait()?.is_some())
     }
 
-    pub async fn cancel(&mut self) -> JfResult<()> {
-        if let Err(e) = self.child.kill().await {
-            match e.kind() {
-                std::io::ErrorKind::InvalidInput => {}
-                _ => return Err(e.into()),
-            }
-        }
-        // self.log_driver.join().await?;
-        Ok(())
-    }
-
     pub async fn join(&mut self) -> JfResult<JoinStatus> {
         let status = self.child.wait().await?;
+        self.process_group.unregister();
         self.log_driver.join().await?;
-        match status.success() {
-            true => Ok(JoinStatus::Succeed),
-            false => Ok(JoinStatus::Failed),
+        Ok(status.into())
+    }
+}
+
+#[cfg(test)]
+mod fixtures {
+    use super::*;
+
+    #[coverage(off)]
+    pub fn params(command: &str, args: &[&str]) -> CommandParams {
+        CommandParams {
+            command: command.to_owned(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..Default::default()
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::util::testutil::{async_test, AsyncFixture};
+    use crate::{
+        job::join_status::Failure,
+        logging::LoggingMock,
+        util::testutil::{async_test, AsyncFixture},
+    };
 
     use super::*;
 
@@ -69,10 +107,9 @@ mod tests {
         async_test(
             #[coverage(off)]
             async {
-                let command = "echo".to_owned();
-                let args = vec!["hello".to_owned()];
+                let params = fixtures::params("echo", &["hello"]);
                 let ctx = Ctx::async_fixture().await;
-                let mut driver = CommandDriver::spawn(ctx, &command, &args).await?;
+                let mut driver = CommandDriver::spawn(ctx, &params).await?;
                 assert!(driver.join().await?.is_succeed());
                 Ok(())
             },
@@ -85,12 +122,64 @@ mod tests {
         async_test(
             #[coverage(off)]
             async {
-                let command = "sleep".to_owned();
-                let args = vec!["10".to_owned()];
+                let params = fixtures::params("sleep", &["10"]);
                 let ctx = Ctx::async_fixture().await;
-                let mut driver = CommandDriver::spawn(ctx, &command, &args).await?;
-                driver.cancel().await?;
-                assert!(driver.join().await?.is_failed());
+                let mut driver = CommandDriver::spawn(ctx.clone(), &params).await?;
+                driver.process_group().cancel(ctx.grace_period())?;
+                assert_eq!(
+                    driver.join().await?,
+                    JoinStatus::Failed(Failure::Signal(libc::SIGTERM))
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn cancel_grandchildren() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let logging_mock = LoggingMock::new().await;
+                let ctx = Ctx::new(logging_mock.logger, "test", false);
+                let params = fixtures::params("sh", &["-c", "sleep 10 & echo $!; wait"]);
+                let mut driver = CommandDriver::spawn(ctx.clone(), &params).await?;
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await; // wait for the grandchild to start
+                driver.process_group().cancel(ctx.grace_period())?;
+                driver.join().await?;
+                let pid: i32 = logging_mock.log_writer.lines()[0]
+                    .trim_start_matches("[I] ")
+                    .parse()
+                    .unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await; // wait for the grandchild to exit
+                let ps = std::process::Command::new("ps")
+                    .args(["-o", "stat=", "-p", &pid.to_string()])
+                    .output()?;
+                let stat = String::from_utf8_lossy(&ps.stdout);
+                assert!(stat.trim().is_empty() || stat.starts_with('Z')); // exited or zombie
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn cancel_after_grace_period() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let ctx = Ctx::async_fixture()
+                    .await
+                    .with_grace_period(std::time::Duration::from_millis(100));
+                let params = fixtures::params("sh", &["-c", "trap '' TERM; sleep 10"]);
+                let mut driver = CommandDriver::spawn(ctx.clone(), &params).await?;
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await; // wait for the trap to be set
+                driver.process_group().cancel(ctx.grace_period())?;
+                assert_eq!(
+                    driver.join().await?,
+                    JoinStatus::Failed(Failure::Signal(libc::SIGKILL))
+                );
                 Ok(())
             },
         )
@@ -102,10 +191,104 @@ mod tests {
         async_test(
             #[coverage(off)]
             async {
-                let command = "false".to_owned();
+                let params = fixtures::params("false", &[]);
+                let ctx = Ctx::async_fixture().await;
+                let mut driver = CommandDriver::spawn(ctx, &params).await?;
+                assert_eq!(driver.join().await?, JoinStatus::Failed(Failure::Code(1)));
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn exit_code() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let params = fixtures::params("sh", &["-c", "exit 3"]);
+                let ctx = Ctx::async_fixture().await;
+                let mut driver = CommandDriver::spawn(ctx, &params).await?;
+                assert_eq!(driver.join().await?.exit_code(), 3);
+                Ok(())
+            },
+        )
+    }
+
+    #[coverage(off)]
+    async fn echo_env(ctx: Ctx, params: CommandParams) -> JfResult<()> {
+        let mut driver = CommandDriver::spawn(ctx, &params).await?;
+        assert!(driver.join().await?.is_succeed());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn env() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let logging_mock = LoggingMock::new().await;
+                let ctx = Ctx::new(logging_mock.logger, "test", false).with_env(
+                    [
+                        ("JF_GLOBAL", "global"),
+                        ("JF_OVERRIDDEN", "global"),
+                        ("JF_ENV_FILE", "global"),
+                    ]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect(),
+                );
+                let mut params = fixtures::params(
+                    "sh",
+                    &[
+                        "-c",
+                        "echo $JF_GLOBAL $JF_OVERRIDDEN $JF_ENV_FILE $JF_QUOTED",
+                    ],
+                );
+                params.env_file = Some("./tests/fixtures/.env".into());
+                params
+                    .env
+                    .insert("JF_OVERRIDDEN".to_owned(), "job".to_owned());
+                echo_env(ctx, params).await?;
+                assert_eq!(
+                    logging_mock.log_writer.lines(),
+                    vec!["[I] global job from-file hello world"]
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn clear_env() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let logging_mock = LoggingMock::new().await;
+                let ctx = Ctx::new(logging_mock.logger, "test", false);
+                let mut params =
+                    fixtures::params("/bin/sh", &["-c", "echo ${HOME:-cleared} $JF_KEPT"]);
+                params.clear_env = true;
+                params.env.insert("JF_KEPT".to_owned(), "kept".to_owned());
+                echo_env(ctx, params).await?;
+                assert_eq!(logging_mock.log_writer.lines(), vec!["[I] cleared kept"]);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn env_file_unexist() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let mut params = fixtures::params("echo", &[]);
+                params.env_file = Some("./tests/unexist/.env".into());
                 let ctx = Ctx::async_fixture().await;
-                let mut driver = CommandDriver::spawn(ctx, &command, &vec![]).await?;
-                assert!(driver.join().await?.is_failed());
+                assert!(CommandDriver::spawn(ctx, &params).await.is_err());
                 Ok(())
             },
         )
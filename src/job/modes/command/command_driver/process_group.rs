@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use crate::util::error::JfResult;
+
+/// process groups which are spawned by jf and not yet reaped
+static ALIVE: LazyLock<Mutex<HashSet<i32>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Process group led by a spawned child
+///
+/// Signals are sent to the whole group so that grandchildren
+/// (e.g. the binary spawned by `cargo run`) are stopped together.
+#[derive(Clone, Copy)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub struct ProcessGroup(i32);
+
+impl ProcessGroup {
+    pub fn register(pgid: u32) -> Self {
+        let pgid = pgid as i32;
+        ALIVE.lock().unwrap().insert(pgid);
+        Self(pgid)
+    }
+
+    pub fn unregister(&self) {
+        ALIVE.lock().unwrap().remove(&self.0);
+    }
+
+    pub fn is_alive(&self) -> bool {
+        ALIVE.lock().unwrap().contains(&self.0)
+    }
+
+    pub fn terminate(&self) -> JfResult<()> {
+        self.signal(libc::SIGTERM)
+    }
+
+    pub fn kill(&self) -> JfResult<()> {
+        self.signal(libc::SIGKILL)
+    }
+
+    /// Terminate gracefully, then kill if it is still alive after `grace_period`
+    pub fn cancel(&self, grace_period: Duration) -> JfResult<()> {
+        self.terminate()?;
+        tokio::spawn({
+            let group = *self;
+            async move {
+                tokio::time::sleep(grace_period).await;
+                let _ = group.kill(); // no-op if already reaped
+            }
+        });
+        Ok(())
+    }
+
+    pub fn signal(&self, signal: i32) -> JfResult<()> {
+        if !self.is_alive() {
+            return Ok(());
+        }
+        // SAFETY: killpg has no memory safety requirements
+        if unsafe { libc::killpg(self.0, signal) } == -1 {
+            let e = std::io::Error::last_os_error();
+            // ESRCH: all processes in the group have already exited
+            if e.raw_os_error() != Some(libc::ESRCH) {
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Force to kill every process group spawned by jf
+pub fn kill_all() {
+    let alive = ALIVE.lock().unwrap().clone();
+    for pgid in alive {
+        let _ = ProcessGroup(pgid).kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::CommandExt;
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn register_unregister() {
+        let group = ProcessGroup::register(u32::MAX - 1);
+        assert!(group.is_alive());
+        group.unregister();
+        assert!(!group.is_alive());
+        assert!(group.kill().is_ok()); // unregistered group is never signaled
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn kill_all_groups() -> JfResult<()> {
+        let mut child = std::process::Command::new("sleep")
+            .arg("10")
+            .process_group(0)
+            .spawn()?;
+        let group = ProcessGroup::register(child.id());
+        kill_all();
+        let status = child.wait()?;
+        group.unregister();
+        assert_eq!(
+            std::os::unix::process::ExitStatusExt::signal(&status),
+            Some(libc::SIGKILL)
+        );
+        Ok(())
+    }
+}
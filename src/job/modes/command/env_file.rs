@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::path::Path;
+
+use crate::util::error::{IntoJfError, JfResult};
+
+/// Load `KEY=VALUE` pairs from dotenv style file
+///
+/// - empty lines and lines starting with `#` are skipped
+/// - `export ` prefix is allowed
+/// - value surrounded by `"` or `'` is unquoted
+pub fn load<P: AsRef<Path>>(path: P) -> JfResult<Vec<(String, String)>> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read env_file({}): {e}", path.display()).into_jf_error())?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(index, line)| {
+            parse_line(line).ok_or(
+                format!(
+                    "Invalid line in env_file({}:{}): {line}",
+                    path.display(),
+                    index + 1
+                )
+                .into_jf_error(),
+            )
+        })
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    let value = value.trim();
+    let value = ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+        .unwrap_or(value);
+    Some((key.to_owned(), value.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn pair(key: &str, value: &str) -> Option<(String, String)> {
+        Some((key.to_owned(), value.to_owned()))
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse() {
+        assert_eq!(parse_line("KEY=value"), pair("KEY", "value"));
+        assert_eq!(parse_line(" KEY = value "), pair("KEY", "value"));
+        assert_eq!(parse_line("export KEY=value"), pair("KEY", "value"));
+        assert_eq!(parse_line(r#"KEY="a b""#), pair("KEY", "a b"));
+        assert_eq!(parse_line("KEY='a b'"), pair("KEY", "a b"));
+        assert_eq!(parse_line("KEY=a=b"), pair("KEY", "a=b"));
+        assert_eq!(parse_line("KEY="), pair("KEY", ""));
+        assert_eq!(parse_line("KEY"), None);
+        assert_eq!(parse_line("=value"), None);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn load_file() -> JfResult<()> {
+        let pairs = load("./tests/fixtures/.env")?;
+        assert_eq!(
+            pairs,
+            vec![
+                ("JF_ENV_FILE".to_owned(), "from-file".to_owned()),
+                ("JF_QUOTED".to_owned(), "hello world".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn load_unexist() {
+        assert!(load("./tests/unexist/.env").is_err());
+    }
+}
@@ -1,25 +1,58 @@
 // SPDX-License-Identifier: MPL-2.0
 mod command_driver;
+mod env_file;
 #[cfg(test)]
 mod tests;
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use tokio::sync::Mutex;
 
 use crate::{
     ctx::Ctx,
-    job::{canceller::Canceller, join_status::JoinStatus, runner::*, Job},
-    util::{error::JfResult, ReadOnly},
+    job::{
+        canceller::Canceller,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        Job,
+    },
+    util::{error::JfResult, template, ReadOnly},
 };
 
-use self::command_driver::CommandDriver;
+pub use self::command_driver::kill_all;
+use self::command_driver::{CommandDriver, ProcessGroup};
 
 #[derive(Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(Default))]
 pub struct CommandParams {
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
+    /// environment variables which override `[env]` and `env_file`
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub env_file: Option<PathBuf>,
+    /// run without environment variables inherited from `jf` process
+    #[serde(default)]
+    pub clear_env: bool,
+    /// working directory (relative to the directory of cfg file)
+    pub cwd: Option<PathBuf>,
+}
+
+impl CommandParams {
+    /// substitute `{{name}}` in `command` and `args`
+    pub fn render(&self, vars: &HashMap<String, String>) -> JfResult<Self> {
+        Ok(Self {
+            command: template::render(&self.command, vars)?,
+            args: template::render_all(&self.args, vars)?,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -27,15 +60,19 @@ pub struct Command {
     ctx: Ctx,
     params: ReadOnly<CommandParams>,
     command_driver: Arc<Mutex<Option<CommandDriver>>>,
+    // kept apart from `command_driver` to cancel without waiting for `join`
+    process_group: Arc<Mutex<Option<ProcessGroup>>>,
     canceller: Canceller,
 }
 
 impl Command {
-    pub fn new(ctx: Ctx, params: CommandParams) -> Self {
+    pub fn new(ctx: Ctx, mut params: CommandParams) -> Self {
+        params.args.extend(ctx.args().iter().cloned());
         Self {
             ctx: ctx.new_span("command"),
             params: params.into(),
             command_driver: Arc::new(Mutex::new(None)),
+            process_group: Arc::new(Mutex::new(None)),
             canceller: Canceller::new(),
         }
     }
@@ -48,6 +85,7 @@ impl Bunshin for Command {
             ctx: self.ctx.clone(),
             params: self.params.clone(),
             command_driver: Arc::new(Mutex::new(None)),
+            process_group: Arc::new(Mutex::new(None)),
             canceller: Canceller::new(),
         }
     }
@@ -68,20 +106,16 @@ impl Runner for Command {
     async fn start(&self) -> JfResult<Self> {
         let mut logger = self.ctx.logger();
         logger.debug("Command starting...").await?;
-        let cd = CommandDriver::spawn(
-            self.ctx.clone(),
-            &self.params.read().command,
-            &self.params.read().args,
-        )
-        .await?;
+        let cd = CommandDriver::spawn(self.ctx.clone(), self.params.read()).await?;
+        self.process_group.lock().await.replace(cd.process_group());
         self.command_driver.lock().await.replace(cd);
         logger.debug("Command started").await?;
         Ok(self.clone())
     }
 
     async fn cancel(&self) -> JfResult<Self> {
-        if let Some(command_driver) = self.command_driver.lock().await.deref_mut() {
-            command_driver.cancel().await?;
+        if let Some(process_group) = self.process_group.lock().await.deref() {
+            process_group.cancel(self.ctx.grace_period())?;
         }
         Ok(self.clone())
     }
@@ -90,7 +124,14 @@ impl Runner for Command {
         if let Some(command_driver) = self.command_driver.lock().await.deref_mut() {
             return command_driver.join().await;
         }
-        return Ok(JoinStatus::Failed);
+        return Ok(JoinStatus::Failed(Failure::Cancelled)); // not yet started
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        if let Some(process_group) = self.process_group.lock().await.deref() {
+            process_group.signal(signal)?;
+        }
+        Ok(())
     }
 
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
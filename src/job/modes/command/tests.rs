@@ -8,6 +8,7 @@ impl AsyncFixture for Command {
         let params = CommandParams {
             command: String::from("sleep"),
             args: vec![String::from("1")],
+            ..Default::default()
         };
         Command::new(Ctx::async_fixture().await, params)
     }
@@ -57,6 +58,28 @@ fn cancel() -> JfResult<()> {
     )
 }
 
+#[test]
+#[coverage(off)]
+fn signal() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let params = CommandParams {
+                command: "sh".into(),
+                args: vec!["-c".into(), "trap 'exit 3' HUP; sleep 10 & wait".into()],
+                ..Default::default()
+            };
+            let command = Command::new(Ctx::async_fixture().await, params);
+            command.signal(libc::SIGHUP).await?; // not yet started
+            command.start().await?;
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await; // wait for trap
+            command.signal(libc::SIGHUP).await?;
+            assert_eq!(command.join().await?, JoinStatus::Failed(Failure::Code(3)));
+            Ok(())
+        },
+    )
+}
+
 #[test]
 #[coverage(off)]
 fn bunshin() -> JfResult<()> {
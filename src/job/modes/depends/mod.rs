@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MPL-2.0
+mod shared;
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use futures::future;
+
+use crate::{
+    ctx::Ctx,
+    job::{
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
+    },
+    util::error::JfResult,
+};
+
+pub use self::shared::SharedJob;
+
+/// Job which runs after all of `depends_on` succeeded
+#[derive(Clone)]
+pub struct Depends {
+    ctx: Ctx,
+    deps: Vec<SharedJob>,
+    job: Box<Job>,
+    canceller: Canceller,
+    handle: TaskHandle,
+    finish_notify: Arc<FinishNotify>,
+}
+
+impl Depends {
+    pub fn new(ctx: Ctx, deps: Vec<SharedJob>, job: Job) -> Self {
+        Self {
+            ctx: ctx.new_span("depends"),
+            deps,
+            job: Box::new(job),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Bunshin for Depends {
+    async fn bunshin(&self) -> Self {
+        for dep in &self.deps {
+            dep.reset().await;
+        }
+        Self {
+            ctx: self.ctx.clone(),
+            deps: self.deps.clone(),
+            job: Box::new(self.job.bunshin().await),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for Depends {
+    async fn is_finished(&self) -> JfResult<bool> {
+        Ok(self.finish_notify.is_finished())
+    }
+}
+
+#[async_trait::async_trait]
+impl Runner for Depends {
+    async fn start(&self) -> JfResult<Self> {
+        let mut logger = self.ctx.logger();
+        logger.debug("Depends starting...").await?;
+        for dep in &self.deps {
+            dep.start().await?; // independent dependencies run concurrently
+        }
+        let handle: JfHandle = tokio::spawn({
+            let deps = self.deps.clone();
+            let job = (*self.job).clone(); // shares state to be cancelled via `self.job`
+            let canceller = self.canceller.clone();
+            let finish_notify = self.finish_notify.clone();
+
+            async move {
+                let statuses = future::join_all(deps.iter().map(|dep| dep.join())).await;
+                for status in statuses {
+                    let status = status?;
+                    if status.is_failed() {
+                        finish_notify.notify();
+                        return Ok(status);
+                    }
+                }
+                if canceller.is_canceled() {
+                    finish_notify.notify();
+                    return Ok(JoinStatus::Failed(Failure::Cancelled));
+                }
+                let job = job.start().await?;
+                if canceller.is_canceled() {
+                    job.cancel().await?; // cancelled while starting
+                }
+                let status = job.join().await?;
+                finish_notify.notify();
+                Ok(status)
+            }
+        });
+        self.handle.set(handle).await;
+        logger.debug("Depends started").await?;
+        Ok(self.clone())
+    }
+
+    async fn cancel(&self) -> JfResult<Self> {
+        self.canceller.cancel();
+        for dep in &self.deps {
+            dep.cancel().await?;
+        }
+        self.job.cancel().await?;
+        Ok(self.clone())
+    }
+
+    async fn join(&self) -> JfResult<JoinStatus> {
+        self.handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.job.signal(signal).await
+    }
+
+    fn set_canceller(&mut self, canceller: Canceller) -> Self {
+        self.canceller = canceller;
+        self.clone()
+    }
+}
+
+impl From<Depends> for Job {
+    fn from(value: Depends) -> Self {
+        Self::Depends(value)
+    }
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::{
+    job::{join_status::JoinStatus, runner::*, Job},
+    util::error::JfResult,
+};
+
+struct State {
+    job: Job,
+    is_started: bool,
+    status: Arc<OnceCell<JoinStatus>>,
+}
+
+/// Job which runs at most once even if several jobs depend on it
+#[derive(Clone)]
+pub struct SharedJob {
+    state: Arc<Mutex<State>>,
+}
+
+impl SharedJob {
+    pub fn new(job: Job) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                job,
+                is_started: false,
+                status: Arc::new(OnceCell::new()),
+            })),
+        }
+    }
+
+    /// start the job unless another dependent has already started it
+    pub async fn start(&self) -> JfResult<()> {
+        let mut state = self.state.lock().await;
+        if !state.is_started {
+            state.job.start().await?;
+            state.is_started = true;
+        }
+        Ok(())
+    }
+
+    pub async fn cancel(&self) -> JfResult<()> {
+        let job = self.state.lock().await.job.clone();
+        job.cancel().await?;
+        Ok(())
+    }
+
+    /// wait for the job; every dependent receives the same status
+    pub async fn join(&self) -> JfResult<JoinStatus> {
+        let (job, status) = {
+            let state = self.state.lock().await;
+            (state.job.clone(), state.status.clone())
+        };
+        status.get_or_try_init(|| job.join()).await.copied()
+    }
+
+    #[cfg(test)]
+    pub async fn is_finished(&self) -> bool {
+        self.state.lock().await.status.initialized()
+    }
+
+    /// make the job runnable again (no-op if another dependent already reset it)
+    pub async fn reset(&self) {
+        let mut state = self.state.lock().await;
+        if state.is_started {
+            *state = State {
+                job: state.job.bunshin().await,
+                is_started: false,
+                status: Arc::new(OnceCell::new()),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::job::modes::{Mock, MockParams};
+    use crate::util::testutil::*;
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn start_once() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let mock = Mock::new(MockParams::fixture());
+                let shared = SharedJob::new(mock.clone().into());
+                assert!(!shared.is_finished().await);
+                shared.start().await?;
+                shared.start().await?;
+                let (a, b) = tokio::join!(shared.join(), shared.join());
+                assert!(a?.is_succeed());
+                assert!(b?.is_succeed());
+                assert!(shared.is_finished().await);
+                mock.assert_is_finished_eq(true);
+
+                shared.reset().await;
+                assert!(!shared.is_finished().await);
+                shared.reset().await; // already reset
+                shared.start().await?;
+                shared.cancel().await?;
+                assert!(shared.join().await?.is_failed());
+                Ok(())
+            },
+        )
+    }
+}
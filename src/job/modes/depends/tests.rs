@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MPL-2.0
+use crate::job::modes::{Mock, MockParams};
+use crate::util::testutil::*;
+
+use super::*;
+
+#[coverage(off)]
+fn mock() -> Mock {
+    Mock::new(MockParams::fixture())
+}
+
+impl AsyncFixture for Depends {
+    async fn async_fixture() -> Self {
+        Depends::new(
+            Ctx::async_fixture().await,
+            vec![SharedJob::new(mock().into()), SharedJob::new(mock().into())],
+            mock().into(),
+        )
+    }
+}
+
+#[test]
+#[coverage(off)]
+fn join() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let depends = Depends::async_fixture().await;
+            assert!(!depends.is_finished().await?);
+            depends.start().await?;
+            assert!(depends.join().await?.is_succeed());
+            assert!(depends.is_finished().await?);
+            depends.job.as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn shared_dependency() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dep_mock = mock();
+            let dep = SharedJob::new(dep_mock.clone().into());
+            let ctx = Ctx::async_fixture().await;
+            let a = Depends::new(ctx.clone(), vec![dep.clone()], mock().into());
+            let b = Depends::new(ctx, vec![dep], mock().into());
+            a.start().await?;
+            b.start().await?;
+            assert!(a.join().await?.is_succeed());
+            assert!(b.join().await?.is_succeed());
+            dep_mock.assert_is_finished_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn failed_dependency() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dep = SharedJob::new(mock().into());
+            let depends =
+                Depends::new(Ctx::async_fixture().await, vec![dep.clone()], mock().into());
+            dep.start().await?;
+            dep.cancel().await?;
+            depends.start().await?;
+            assert_eq!(
+                depends.join().await?,
+                JoinStatus::Failed(Failure::Cancelled)
+            );
+            depends.job.as_mock().assert_is_started_eq(false);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn cancel() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let depends = Depends::async_fixture().await;
+            depends.start().await?.cancel().await?;
+            assert!(depends.join().await?.is_failed());
+            assert!(depends.is_finished().await?);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn bunshin() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let origin = Depends::async_fixture().await;
+            origin.start().await?.join().await?;
+            let bunshin = origin.bunshin().await;
+            assert!(!bunshin.is_finished().await?);
+            for dep in &bunshin.deps {
+                assert!(!dep.is_finished().await);
+            }
+            assert!(bunshin.start().await?.join().await?.is_succeed());
+            Ok(())
+        },
+    )
+}
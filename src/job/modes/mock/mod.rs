@@ -2,13 +2,14 @@
 mod tests;
 
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicUsize;
 use std::sync::{atomic::Ordering, Arc};
 
 use tokio::sync::Mutex;
 
 use crate::job::canceller::Canceller;
-use crate::job::join_status::JoinStatus;
+use crate::job::join_status::{Failure, JoinStatus};
 use crate::{
     job::{runner::*, Job},
     util::{error::JfResult, testutil::Fixture},
@@ -30,6 +31,7 @@ pub struct Mock {
     is_started: Arc<AtomicBool>,
     is_running: Arc<AtomicBool>,
     is_finished: Arc<AtomicBool>,
+    last_signal: Arc<AtomicI32>,
     canceller: Canceller,
     handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
@@ -43,6 +45,7 @@ impl Mock {
             is_started: Arc::new(AtomicBool::new(false)),
             is_running: Arc::new(AtomicBool::new(false)),
             is_finished: Arc::new(AtomicBool::new(false)),
+            last_signal: Arc::new(AtomicI32::new(0)),
             canceller: Canceller::new(),
             handle: Arc::new(Mutex::new(None)),
         }
@@ -127,6 +130,19 @@ impl Mock {
         self.clone()
     }
 
+    #[coverage(off)]
+    pub fn assert_last_signal_eq(&self, signal: i32) -> Self {
+        assert_eq!(
+            self.last_signal.load(Ordering::Relaxed),
+            signal,
+            "Mock({}).last_signal is expected {} but {}",
+            self.id,
+            signal,
+            self.last_signal.load(Ordering::Relaxed)
+        );
+        self.clone()
+    }
+
     #[coverage(off)]
     pub fn assert_is_cancelled_eq(&self, is_cancelled: bool) -> Self {
         assert_eq!(
@@ -195,14 +211,26 @@ impl Runner for Mock {
     }
 
     async fn join(&self) -> JfResult<JoinStatus> {
+        // clone to keep the handle abortable by `cancel` while waiting
+        let handle = self.handle.lock().await.as_ref().map(|h| h.abort_handle());
+        if let Some(handle) = handle {
+            while !handle.is_finished() {
+                tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+            }
+        }
         self.is_running.store(false, Ordering::Relaxed);
         self.is_finished.store(true, Ordering::Relaxed);
         match self.canceller.is_canceled() {
-            true => Ok(JoinStatus::Failed),
+            true => Ok(JoinStatus::Failed(Failure::Cancelled)),
             false => Ok(JoinStatus::Succeed),
         }
     }
 
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.last_signal.store(signal, Ordering::Relaxed);
+        Ok(())
+    }
+
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
         self.canceller = canceller;
         self.clone()
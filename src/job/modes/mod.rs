@@ -1,25 +1,134 @@
 // SPDX-License-Identifier: MPL-2.0
 mod command;
+mod depends;
 mod parallel;
+mod retry;
 mod sequential;
 mod shell;
+mod timeout;
 mod watch;
 
+use crate::{
+    ctx::Ctx,
+    jobdef::{call, Agent, JobdefPool},
+    util::error::{IntoJfError, JfResult},
+};
+
+use super::{join_status::JoinStatus, Job};
+
 pub(super) use command::Command;
+pub(super) use depends::Depends;
 pub(super) use parallel::Parallel;
+pub(super) use retry::Retry;
 pub(super) use sequential::Sequential;
 pub(super) use shell::Shell;
+pub(super) use timeout::Timeout;
 pub(super) use watch::Watch;
 
+pub use command::kill_all;
 pub use command::CommandParams;
+pub use depends::SharedJob;
 pub use parallel::ParallelParams;
+pub use retry::RetryParams;
 pub use sequential::SequentialParams;
 pub use shell::ShellParams;
 pub use watch::WatchParams;
 
+/// Build child jobs of composite mode
+///
+/// Pass-through args in `ctx` are given only to the child named by `forward_args`.
+fn build_children(
+    ctx: &Ctx,
+    mode: &str,
+    jobs: Vec<String>,
+    forward_args: Option<&String>,
+    pool: &JobdefPool,
+) -> JfResult<Vec<Job>> {
+    let is_forwarded =
+        |job: &String| forward_args.is_some_and(|f| f == job || f == call::name(job));
+    match forward_args {
+        Some(name) if !jobs.iter().any(is_forwarded) => {
+            return Err(format!("mode={mode} forward_args({name}) is not in jobs").into_jf_error());
+        }
+        None if !ctx.args().is_empty() => {
+            return Err(format!("mode={mode} needs forward_args to receive args").into_jf_error());
+        }
+        _ => {}
+    }
+    jobs.into_iter()
+        .map(|job_name| {
+            let ctx = if is_forwarded(&job_name) {
+                ctx.clone()
+            } else {
+                ctx.without_args()
+            };
+            pool.build(ctx, job_name, Agent::Job)
+        })
+        .collect()
+}
+
+/// Log failed jobs of composite mode (e.g. `parallel: 1 of 2 jobs failed: test (exit code 1)`)
+async fn summarize_failures<'a>(
+    ctx: &Ctx,
+    mode: &str,
+    total: usize,
+    results: impl Iterator<Item = (&'a String, &'a JoinStatus)>,
+) -> JfResult<()> {
+    let failed = results
+        .filter_map(|(name, status)| match status {
+            JoinStatus::Failed(failure) => Some(format!("{name} ({failure})")),
+            JoinStatus::Succeed => None,
+        })
+        .collect::<Vec<_>>();
+    if !failed.is_empty() {
+        ctx.logger()
+            .error(format!(
+                "{mode}: {} of {total} jobs failed: {}",
+                failed.len(),
+                failed.join(", ")
+            ))
+            .await?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 pub(super) use mock::Mock;
 #[cfg(test)]
 pub use mock::MockParams;
+
+#[cfg(test)]
+mod tests {
+    use crate::{job::join_status::Failure, logging::LoggingMock, util::testutil::*};
+
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn summarize_failures() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let logging_mock = LoggingMock::new().await;
+                let ctx = Ctx::new(logging_mock.logger, "test", true);
+                let names = ["build".to_string(), "test".to_string()];
+
+                let statuses = [JoinStatus::Succeed, JoinStatus::Succeed];
+                super::summarize_failures(&ctx, "parallel", 2, names.iter().zip(&statuses)).await?;
+                ctx.logger().flush().await?;
+                assert!(logging_mock.log_writer.lines().is_empty());
+
+                let statuses = [JoinStatus::Succeed, JoinStatus::Failed(Failure::Code(1))];
+                super::summarize_failures(&ctx, "parallel", 2, names.iter().zip(&statuses)).await?;
+                ctx.logger().flush().await?;
+                assert_eq!(
+                    logging_mock.log_writer.lines(),
+                    vec!["[E] parallel: 1 of 2 jobs failed: test (exit code 1)"]
+                );
+                Ok(())
+            },
+        )
+    }
+}
@@ -2,50 +2,105 @@
 #[cfg(test)]
 mod tests;
 
-use std::{
-    ops::{Deref, DerefMut},
-    sync::Arc,
-};
+use std::{collections::HashMap, num::NonZeroUsize, ops::Deref, path::PathBuf, sync::Arc};
 
-use tokio::{sync::Mutex, task::JoinHandle};
+use futures::{stream::FuturesUnordered, StreamExt};
+use tokio::sync::Mutex;
 
 use crate::{
     ctx::Ctx,
     job::{
-        canceller::Canceller, finish_notify::FinishNotify, join_status::JoinStatus, runner::*, Job,
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
     },
-    jobdef::{Agent, JobdefPool},
-    util::error::JfResult,
+    jobdef::JobdefPool,
+    util::{error::JfResult, template},
 };
 
 #[derive(Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(Default))]
 pub struct ParallelParams {
     pub jobs: Vec<String>,
+    /// default working directory of child jobs
+    pub cwd: Option<PathBuf>,
+    /// child job which receives args passed after `--`
+    pub forward_args: Option<String>,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    /// max number of jobs running at once (default: `--jobs` of cli)
+    pub max_concurrency: Option<NonZeroUsize>,
+}
+
+/// What to do with the other jobs when one of them failed
+#[derive(Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[cfg_attr(test, derive(Debug))]
+pub enum OnFailure {
+    /// let the other jobs run to completion
+    #[default]
+    #[serde(rename = "continue")]
+    Continue,
+    /// cancel the other jobs
+    #[serde(rename = "cancel_others")]
+    CancelOthers,
+}
+
+impl ParallelParams {
+    /// substitute `{{name}}` in `jobs` and `forward_args`
+    pub fn render(&self, vars: &HashMap<String, String>) -> JfResult<Self> {
+        Ok(Self {
+            jobs: template::render_all(&self.jobs, vars)?,
+            forward_args: self
+                .forward_args
+                .as_ref()
+                .map(|job| template::render(job, vars))
+                .transpose()?,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Clone)]
 pub struct Parallel {
     ctx: Ctx,
+    job_names: Vec<String>,
     jobs: Vec<Job>,
+    on_failure: OnFailure,
+    /// `usize::MAX` if unbounded
+    max_concurrency: usize,
     canceller: Canceller,
     running_jobs: Arc<Mutex<Vec<Job>>>,
-    finish_handle: Arc<Mutex<Option<JoinHandle<JfResult<()>>>>>,
+    finish_handle: TaskHandle,
     finish_notify: Arc<FinishNotify>,
 }
 
 impl Parallel {
     pub fn new(ctx: Ctx, params: ParallelParams, pool: JobdefPool) -> JfResult<Self> {
-        let jobs = params
-            .jobs
-            .into_iter()
-            .map(|job_name| pool.build(ctx.clone(), job_name, Agent::Job))
-            .collect::<JfResult<Vec<Job>>>()?;
+        let ctx = ctx.inherit_cwd(params.cwd);
+        let job_names = params.jobs.clone();
+        let jobs = super::build_children(
+            &ctx,
+            "parallel",
+            params.jobs,
+            params.forward_args.as_ref(),
+            &pool,
+        )?;
         Ok(Self {
             ctx: ctx.new_span("parallel"),
+            job_names,
             jobs: jobs.clone(),
+            on_failure: params.on_failure,
+            max_concurrency: params
+                .max_concurrency
+                .map(NonZeroUsize::get)
+                .or(ctx.max_concurrency())
+                .unwrap_or(usize::MAX),
             canceller: Canceller::new(),
             running_jobs: Arc::new(Mutex::new(jobs)),
-            finish_handle: Arc::new(Mutex::new(None)),
+            finish_handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         })
     }
@@ -54,12 +109,16 @@ impl Parallel {
 #[async_trait::async_trait]
 impl Bunshin for Parallel {
     async fn bunshin(&self) -> Self {
+        let jobs = self.jobs.bunshin().await;
         Self {
             ctx: self.ctx.clone(),
-            jobs: self.jobs.bunshin().await,
+            job_names: self.job_names.clone(),
+            jobs: jobs.clone(),
+            on_failure: self.on_failure,
+            max_concurrency: self.max_concurrency,
             canceller: Canceller::new(),
-            running_jobs: Arc::new(Mutex::new(self.running_jobs.lock().await.bunshin().await)),
-            finish_handle: Arc::new(Mutex::new(None)),
+            running_jobs: Arc::new(Mutex::new(jobs)),
+            finish_handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         }
     }
@@ -68,12 +127,7 @@ impl Bunshin for Parallel {
 #[async_trait::async_trait]
 impl Checker for Parallel {
     async fn is_finished(&self) -> JfResult<bool> {
-        for job in self.running_jobs.lock().await.deref_mut() {
-            if !job.is_finished().await? {
-                return Ok(false);
-            }
-        }
-        Ok(true)
+        Ok(self.finish_notify.is_finished())
     }
 }
 
@@ -82,41 +136,122 @@ impl Runner for Parallel {
     async fn start(&self) -> JfResult<Self> {
         let mut logger = self.ctx.logger();
         logger.debug("Parallel starting...").await?;
-        for job in self.running_jobs.lock().await.deref() {
-            job.start().await?;
+        let first = self.max_concurrency.min(self.jobs.len());
+        // running jobs are joined even if a job fails to start
+        let mut start_error = None;
+        let mut started = 0;
+        for job in &self.running_jobs.lock().await[..first] {
+            if let Err(e) = job.start().await {
+                start_error = Some(e);
+                break;
+            }
+            started += 1;
         }
-        let handle = tokio::spawn({
+        let handle: JfHandle = tokio::spawn({
+            let ctx = self.ctx.clone();
+            let job_names = self.job_names.clone();
             let jobs = self.jobs.clone();
+            let on_failure = self.on_failure;
+            let canceller = self.canceller.clone();
             let finish_notify = self.finish_notify.clone();
             async move {
-                for job in jobs {
-                    job.join().await?;
+                let mut start_error = start_error;
+                let result = async {
+                    let join = |index: usize| {
+                        let job = jobs[index].clone();
+                        async move { (index, job.join().await) }
+                    };
+                    // jobs never started are reported as cancelled
+                    let mut statuses = vec![JoinStatus::Failed(Failure::Cancelled); jobs.len()];
+                    let mut pending = (0..started).map(join).collect::<FuturesUnordered<_>>();
+                    let mut next = started;
+                    let mut is_others_cancelled = false;
+                    let mut is_finished = vec![false; jobs.len()];
+                    // jobs cancelled by `cancel_others`, whose failures are not reported
+                    let mut is_cancelled_by_others = vec![false; jobs.len()];
+                    while let Some((index, status)) = pending.next().await {
+                        let status = status?;
+                        is_finished[index] = true;
+                        // e.g. a command killed by SIGTERM is cancelled, not failed by itself
+                        statuses[index] = match status {
+                            JoinStatus::Failed(_) if is_cancelled_by_others[index] => {
+                                JoinStatus::Failed(Failure::Cancelled)
+                            }
+                            status => status,
+                        };
+                        if statuses[index].is_failed()
+                            && on_failure == OnFailure::CancelOthers
+                            && !is_others_cancelled
+                        {
+                            is_others_cancelled = true;
+                            for (other, job) in jobs.iter().enumerate() {
+                                if !is_finished[other] {
+                                    is_cancelled_by_others[other] = true;
+                                    job.cancel().await?;
+                                }
+                            }
+                        }
+                        // start queued job in the freed slot
+                        if next < jobs.len()
+                            && !is_others_cancelled
+                            && !canceller.is_canceled()
+                            && start_error.is_none()
+                        {
+                            match jobs[next].start().await {
+                                Ok(job) => {
+                                    if canceller.is_canceled() {
+                                        job.cancel().await?; // cancelled while starting
+                                    }
+                                    pending.push(join(next));
+                                    next += 1;
+                                }
+                                Err(e) => start_error = Some(e),
+                            }
+                        }
+                    }
+                    drop(pending);
+                    if let Some(e) = start_error {
+                        return Err(e);
+                    }
+                    if !canceller.is_canceled() {
+                        let results = job_names
+                            .iter()
+                            .zip(&statuses)
+                            .zip(&is_cancelled_by_others)
+                            .filter(|(_, is_cancelled)| !**is_cancelled)
+                            .map(|(result, _)| result);
+                        super::summarize_failures(&ctx, "parallel", jobs.len(), results).await?;
+                    }
+                    Ok(Self::aggregate(&statuses))
                 }
+                .await;
+                // `join` waits for the notification even if the task failed
                 finish_notify.notify();
-                Ok(())
+                result
             }
         });
-        self.finish_handle.lock().await.replace(handle);
+        self.finish_handle.set(handle).await;
         logger.debug("Parallel started").await?;
         Ok(self.clone())
     }
 
     async fn cancel(&self) -> JfResult<Self> {
+        self.canceller.cancel(); // prevent queued jobs from starting
         for job in self.running_jobs.lock().await.deref() {
             job.cancel().await?;
         }
-        self.canceller.cancel();
         Ok(self.clone())
     }
 
     async fn join(&self) -> JfResult<JoinStatus> {
-        self.finish_notify.wait().await;
-        for job in self.running_jobs.lock().await.deref_mut() {
-            if let JoinStatus::Failed = job.join().await? {
-                return Ok(JoinStatus::Failed);
-            }
+        self.finish_handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        for job in self.running_jobs.lock().await.deref() {
+            job.signal(signal).await?;
         }
-        return Ok(JoinStatus::Succeed);
+        Ok(())
     }
 
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
@@ -125,6 +260,20 @@ impl Runner for Parallel {
     }
 }
 
+impl Parallel {
+    /// Aggregated status is the first failure in the order of `jobs`
+    ///
+    /// Jobs cancelled by `on_failure = "cancel_others"` are `Failed(Cancelled)`, reported only if no other failure exists.
+    fn aggregate(statuses: &[JoinStatus]) -> JoinStatus {
+        let failures = || statuses.iter().filter(|status| status.is_failed());
+        failures()
+            .find(|status| !matches!(status, JoinStatus::Failed(Failure::Cancelled)))
+            .or(failures().next())
+            .copied()
+            .unwrap_or(JoinStatus::Succeed)
+    }
+}
+
 impl From<Parallel> for Job {
     fn from(value: Parallel) -> Self {
         Self::Parallel(value)
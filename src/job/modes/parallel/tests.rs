@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::util::testutil::*;
 
+use crate::{jobdef::Jobdef, util::error::IntoJfError};
+
 use super::*;
 
 impl TryAsyncFixture for Parallel {
@@ -8,6 +10,7 @@ impl TryAsyncFixture for Parallel {
     async fn try_async_fixture() -> JfResult<Self> {
         let params = ParallelParams {
             jobs: vec!["fast".into(), "fast".into()],
+            ..Default::default()
         };
         Parallel::new(
             Ctx::async_fixture().await,
@@ -27,6 +30,7 @@ fn invalid_new_with_unknown_job() -> JfResult<()> {
                 Ctx::async_fixture().await,
                 ParallelParams {
                     jobs: vec!["mock".into(), "mock".into()],
+                    ..Default::default()
                 },
                 JobdefPool::new(vec![]),
             );
@@ -138,3 +142,231 @@ fn is_finished_not_yet_started() -> JfResult<()> {
         },
     )
 }
+
+#[coverage(off)]
+async fn failing_parallel(on_failure: OnFailure) -> JfResult<Parallel> {
+    let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    };
+    let pool = JobdefPool::new(vec![
+        jobdef(
+            "slow",
+            "mode = \"mock\"\neach_sleep_time = 100\nsleep_count = 100",
+        )?,
+        jobdef("fail", "command = \"sh\"\nargs = [\"-c\", \"exit 2\"]")?,
+    ]);
+    let params = ParallelParams {
+        jobs: vec!["slow".into(), "fail".into()],
+        on_failure,
+        ..Default::default()
+    };
+    Parallel::new(Ctx::async_fixture().await, params, pool)
+}
+
+#[test]
+#[coverage(off)]
+fn on_failure_continue() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let p = failing_parallel(OnFailure::Continue).await?;
+            let status = p.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(2)));
+            p.jobs[0].as_mock().assert_is_cancelled_eq(false);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn on_failure_cancel_others() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let p = failing_parallel(OnFailure::CancelOthers).await?;
+            let status = p.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(2)));
+            p.jobs[0].as_mock().assert_is_cancelled_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn on_failure_cancel_others_commands() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let logging_mock = crate::logging::LoggingMock::new().await;
+            let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+                Jobdef::new(name.into(), toml::from_str(cfg)?)
+            };
+            let pool = JobdefPool::new(vec![
+                jobdef("slow", "command = \"sleep\"\nargs = [\"10\"]")?,
+                jobdef("fail", "mode = \"shell\"\nscript = \"sleep 0.1; exit 7\"")?,
+            ]);
+            let params = ParallelParams {
+                jobs: vec!["slow".into(), "fail".into()],
+                on_failure: OnFailure::CancelOthers,
+                ..Default::default()
+            };
+            let ctx = Ctx::new(logging_mock.logger, "test", false);
+            let p = Parallel::new(ctx.clone(), params, pool)?;
+            // `slow` killed by SIGTERM is not the failure
+            let status = p.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(7)));
+            ctx.logger().flush().await?;
+            assert!(logging_mock
+                .log_writer
+                .lines()
+                .contains(&"[E] parallel: 1 of 2 jobs failed: fail (exit code 7)".to_string()));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn aggregate() {
+    let cancelled = JoinStatus::Failed(Failure::Cancelled);
+    let failed = JoinStatus::Failed(Failure::Code(1));
+    assert_eq!(
+        Parallel::aggregate(&[JoinStatus::Succeed, JoinStatus::Succeed]),
+        JoinStatus::Succeed
+    );
+    assert_eq!(Parallel::aggregate(&[cancelled, failed]), failed);
+    assert_eq!(
+        Parallel::aggregate(&[JoinStatus::Succeed, cancelled]),
+        cancelled
+    );
+}
+
+#[test]
+#[coverage(off)]
+fn deserialize_on_failure() -> JfResult<()> {
+    let params: ParallelParams = toml::from_str("jobs = []\non_failure = \"cancel_others\"")?;
+    assert_eq!(params.on_failure, OnFailure::CancelOthers);
+    let params: ParallelParams = toml::from_str("jobs = []")?;
+    assert_eq!(params.on_failure, OnFailure::Continue);
+    Ok(())
+}
+
+#[coverage(off)]
+async fn bounded_parallel(jobs: &[&str]) -> JfResult<Parallel> {
+    let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    };
+    let pool = JobdefPool::new(vec![
+        jobdef(
+            "fast",
+            "mode = \"mock\"\neach_sleep_time = 1\nsleep_count = 1",
+        )?,
+        jobdef("sleep", "command = \"sleep\"\nargs = [\"10\"]")?,
+        jobdef("missing", "command = \"jf-missing-command\"")?,
+    ]);
+    let params = ParallelParams {
+        jobs: jobs.iter().map(|job| job.to_string()).collect(),
+        max_concurrency: NonZeroUsize::new(1),
+        ..Default::default()
+    };
+    Parallel::new(Ctx::async_fixture().await, params, pool)
+}
+
+#[test]
+#[coverage(off)]
+fn max_concurrency() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let p = bounded_parallel(&["fast", "fast", "fast"]).await?;
+            p.start().await?;
+            p.jobs[0].as_mock().assert_is_started_eq(true);
+            p.jobs[1].as_mock().assert_is_started_eq(false);
+            p.jobs[2].as_mock().assert_is_started_eq(false);
+            assert!(p.join().await?.is_succeed());
+            for job in p.jobs {
+                job.as_mock().assert_is_started_eq(true);
+            }
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn default_concurrency() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let params = ParallelParams {
+                jobs: vec!["fast".into(); 10],
+                ..Default::default()
+            };
+            let pool: JobdefPool = TryFixture::try_fixture()?;
+            let p = Parallel::new(Ctx::async_fixture().await, params.clone(), pool.clone())?;
+            p.start().await?;
+            for job in &p.jobs {
+                job.as_mock().assert_is_started_eq(true); // unbounded
+            }
+            p.join().await?;
+
+            let ctx = Ctx::async_fixture().await.with_max_concurrency(Some(2)); // `-j 2`
+            let p = Parallel::new(ctx, params, pool)?;
+            p.start().await?;
+            p.jobs[1].as_mock().assert_is_started_eq(true);
+            p.jobs[2].as_mock().assert_is_started_eq(false);
+            p.join().await?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn cancel_queued() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let p = bounded_parallel(&["sleep", "fast"]).await?;
+            let status = p.start().await?.cancel().await?.join().await?;
+            assert!(status.is_failed());
+            p.jobs[1]
+                .as_mock()
+                .assert_is_started_eq(false)
+                .assert_is_cancelled_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn deserialize_max_concurrency() -> JfResult<()> {
+    let params: ParallelParams = toml::from_str("jobs = []\nmax_concurrency = 4")?;
+    assert_eq!(params.max_concurrency, NonZeroUsize::new(4));
+    assert!(toml::from_str::<ParallelParams>("jobs = []\nmax_concurrency = 0").is_err());
+    Ok(())
+}
+
+#[test]
+#[coverage(off)]
+fn start_error() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            for jobs in [["fast", "missing"], ["missing", "fast"]] {
+                let p = bounded_parallel(&jobs).await?;
+                let result = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+                    p.start().await?.join().await
+                })
+                .await
+                .map_err(|_| "joining parallel timed out".into_jf_error())?;
+                assert!(result.is_err());
+                assert!(p.is_finished().await?);
+            }
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: MPL-2.0
+#[cfg(test)]
+mod tests;
+
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+
+use tokio::sync::Mutex;
+
+use crate::{
+    ctx::Ctx,
+    job::{
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
+    },
+    util::error::JfResult,
+};
+
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum Backoff {
+    /// wait `initial_ms` before every retry
+    #[default]
+    #[serde(rename = "fixed")]
+    Fixed,
+    /// double the wait on every retry
+    #[serde(rename = "exponential")]
+    Exponential,
+}
+
+#[derive(Clone, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryParams {
+    /// max number of runs including the first one
+    pub attempts: NonZeroU32,
+    #[serde(default)]
+    pub backoff: Backoff,
+    #[serde(default)]
+    pub initial_ms: u64,
+}
+
+impl RetryParams {
+    /// wait before the run after `attempt` (starting from 1) failed
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = match self.backoff {
+            Backoff::Fixed => 1,
+            Backoff::Exponential => 2u64.saturating_pow(attempt - 1),
+        };
+        Duration::from_millis(self.initial_ms.saturating_mul(factor))
+    }
+}
+
+/// Job which reruns a fresh copy of the job after it failed
+#[derive(Clone)]
+pub struct Retry {
+    ctx: Ctx,
+    params: RetryParams,
+    job: Arc<Mutex<Job>>,
+    canceller: Canceller,
+    handle: TaskHandle,
+    finish_notify: Arc<FinishNotify>,
+}
+
+impl Retry {
+    pub fn new(ctx: Ctx, params: RetryParams, job: Job) -> Self {
+        Self {
+            ctx: ctx.new_span("retry"),
+            params,
+            job: Arc::new(Mutex::new(job)),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+/// sleep for `duration`, but wake up early if cancelled
+async fn sleep_unless_cancelled(duration: Duration, canceller: &Canceller) {
+    const TICK: Duration = Duration::from_millis(50);
+    let mut remaining = duration;
+    while !remaining.is_zero() && !canceller.is_canceled() {
+        let tick = remaining.min(TICK);
+        tokio::time::sleep(tick).await;
+        remaining -= tick;
+    }
+}
+
+#[async_trait::async_trait]
+impl Bunshin for Retry {
+    async fn bunshin(&self) -> Self {
+        Self {
+            ctx: self.ctx.clone(),
+            params: self.params.clone(),
+            job: Arc::new(Mutex::new(self.job.lock().await.bunshin().await)),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for Retry {
+    async fn is_finished(&self) -> JfResult<bool> {
+        Ok(self.finish_notify.is_finished())
+    }
+}
+
+#[async_trait::async_trait]
+impl Runner for Retry {
+    async fn start(&self) -> JfResult<Self> {
+        let mut logger = self.ctx.logger();
+        logger.debug("Retry starting...").await?;
+        self.job.lock().await.start().await?;
+        let handle: JfHandle = tokio::spawn({
+            let mut logger = self.ctx.logger();
+            let params = self.params.clone();
+            let job = self.job.clone();
+            let canceller = self.canceller.clone();
+            let finish_notify = self.finish_notify.clone();
+
+            async move {
+                let attempts = params.attempts.get();
+                let mut attempt = 1;
+                let status = loop {
+                    // not locked while joining to be cancelled
+                    let current = job.lock().await.clone();
+                    let status = current.join().await?;
+                    let JoinStatus::Failed(failure) = status else {
+                        break status;
+                    };
+                    if canceller.is_canceled() || attempt == attempts {
+                        break status;
+                    }
+                    let delay = params.delay(attempt);
+                    logger
+                        .warn(format!(
+                            "attempt {attempt}/{attempts} failed ({failure}), retrying in {}ms",
+                            delay.as_millis()
+                        ))
+                        .await?;
+                    sleep_unless_cancelled(delay, &canceller).await;
+                    if canceller.is_canceled() {
+                        finish_notify.notify();
+                        return Ok(JoinStatus::Failed(Failure::Cancelled));
+                    }
+                    attempt += 1;
+                    let current = job.lock().await.reset().await?.start().await?;
+                    if canceller.is_canceled() {
+                        current.cancel().await?; // cancelled while starting
+                    }
+                };
+                finish_notify.notify();
+                Ok(status)
+            }
+        });
+        self.handle.set(handle).await;
+        logger.debug("Retry started").await?;
+        Ok(self.clone())
+    }
+
+    async fn cancel(&self) -> JfResult<Self> {
+        self.canceller.cancel();
+        self.job.lock().await.cancel().await?;
+        Ok(self.clone())
+    }
+
+    async fn join(&self) -> JfResult<JoinStatus> {
+        self.handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.job.lock().await.signal(signal).await
+    }
+
+    fn set_canceller(&mut self, canceller: Canceller) -> Self {
+        self.canceller = canceller;
+        self.clone()
+    }
+}
+
+impl From<Retry> for Job {
+    fn from(value: Retry) -> Self {
+        Self::Retry(value)
+    }
+}
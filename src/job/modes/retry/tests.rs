@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::time::Instant;
+
+use crate::job::modes::{Command, CommandParams};
+use crate::util::testutil::*;
+
+use super::*;
+
+#[coverage(off)]
+fn params(attempts: u32, backoff: Backoff, initial_ms: u64) -> RetryParams {
+    RetryParams {
+        attempts: NonZeroU32::new(attempts).unwrap(),
+        backoff,
+        initial_ms,
+    }
+}
+
+#[coverage(off)]
+async fn sh(script: &str) -> Job {
+    let params = CommandParams {
+        command: "sh".into(),
+        args: vec!["-c".into(), script.into()],
+        ..Default::default()
+    };
+    Command::new(Ctx::async_fixture().await, params).into()
+}
+
+#[test]
+#[coverage(off)]
+fn delay() {
+    let fixed = params(3, Backoff::Fixed, 500);
+    assert_eq!(fixed.delay(1), Duration::from_millis(500));
+    assert_eq!(fixed.delay(2), Duration::from_millis(500));
+    let exponential = params(3, Backoff::Exponential, 500);
+    assert_eq!(exponential.delay(1), Duration::from_millis(500));
+    assert_eq!(exponential.delay(3), Duration::from_millis(2000));
+}
+
+#[test]
+#[coverage(off)]
+fn succeed_after_retry() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let counter = std::env::temp_dir().join(format!("jf-retry-{}", std::process::id()));
+            let script = format!(
+                r#"n=$(($(cat "{0}" 2>/dev/null || echo 0) + 1)); echo $n > "{0}"; test $n -ge 3"#,
+                counter.display()
+            );
+            let retry = Retry::new(
+                Ctx::async_fixture().await,
+                params(3, Backoff::Exponential, 1),
+                sh(&script).await,
+            );
+            let status = retry.start().await?.join().await?;
+            std::fs::remove_file(counter)?;
+            assert!(status.is_succeed());
+            assert!(retry.is_finished().await?);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn fail_after_attempts() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let retry = Retry::new(
+                Ctx::async_fixture().await,
+                params(2, Backoff::Fixed, 1),
+                sh("exit 4").await,
+            );
+            let status = retry.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(4)));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn cancel_while_waiting() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let retry = Retry::new(
+                Ctx::async_fixture().await,
+                params(3, Backoff::Fixed, 60_000),
+                sh("exit 1").await,
+            );
+            retry.start().await?;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let begin = Instant::now();
+            let status = retry.cancel().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Cancelled));
+            assert!(begin.elapsed() < Duration::from_secs(1));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn bunshin() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let origin = Retry::new(
+                Ctx::async_fixture().await,
+                params(1, Backoff::Fixed, 0),
+                sh("exit 0").await,
+            );
+            origin.start().await?.join().await?;
+            assert!(origin.is_finished().await?);
+            let bunshin = origin.bunshin().await;
+            assert!(!bunshin.is_finished().await?);
+            assert!(bunshin.start().await?.join().await?.is_succeed());
+            Ok(())
+        },
+    )
+}
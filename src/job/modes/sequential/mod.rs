@@ -2,33 +2,92 @@
 #[cfg(test)]
 mod tests;
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use tokio::sync::Mutex;
 
 use crate::{
     ctx::Ctx,
     job::{
-        canceller::Canceller, finish_notify::FinishNotify, join_status::JoinStatus, runner::*, Job,
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
     },
     jobdef::{Agent, JobdefPool},
     util::{
         error::{IntoJfError, JfResult},
-        ReadOnly,
+        template, ReadOnly,
     },
 };
 
 #[derive(Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(Default))]
 pub struct SequentialParams {
     pub jobs: Vec<String>,
+    /// default working directory of child jobs
+    pub cwd: Option<PathBuf>,
+    /// child job which receives args passed after `--`
+    pub forward_args: Option<String>,
+    /// run the rest of `jobs` even if one of them failed
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// jobs which always run after `jobs` (even if they failed or were cancelled)
+    #[serde(default)]
+    pub finally: Vec<String>,
+}
+
+impl SequentialParams {
+    /// substitute `{{name}}` in `jobs`, `forward_args` and `finally`
+    pub fn render(&self, vars: &HashMap<String, String>) -> JfResult<Self> {
+        Ok(Self {
+            jobs: template::render_all(&self.jobs, vars)?,
+            finally: template::render_all(&self.finally, vars)?,
+            forward_args: self
+                .forward_args
+                .as_ref()
+                .map(|job| template::render(job, vars))
+                .transpose()?,
+            ..self.clone()
+        })
+    }
+}
+
+/// job which is running, `Finally` is cancelled only after the grace period
+#[derive(Clone)]
+enum Current {
+    Job(Job),
+    Finally(Job),
+}
+
+impl Current {
+    fn job(&self) -> &Job {
+        match self {
+            Current::Job(job) | Current::Finally(job) => job,
+        }
+    }
+}
+
+/// cancel `job` after `grace_period` (no-op if it has finished)
+fn cancel_after_grace_period(job: Job, grace_period: Duration) {
+    tokio::spawn(async move {
+        tokio::time::sleep(grace_period).await;
+        job.cancel().await
+    });
 }
 
 #[derive(Clone)]
 pub struct Sequential {
     ctx: Ctx,
+    job_names: Vec<String>,
     jobs: ReadOnly<Vec<Job>>,
+    continue_on_error: bool,
+    finally: ReadOnly<Vec<Job>>,
     canceller: Canceller,
-    handle: Arc<Mutex<Option<JfHandle>>>,
+    current_job: Arc<Mutex<Option<Current>>>,
+    handle: TaskHandle,
     finish_notify: Arc<FinishNotify>,
 }
 
@@ -37,16 +96,29 @@ impl Sequential {
         if params.jobs.is_empty() {
             return Err("mode=sequential must have at least one job".into_jf_error());
         }
-        let jobs = params
-            .jobs
+        let ctx = ctx.inherit_cwd(params.cwd);
+        let job_names = params.jobs.clone();
+        let jobs = super::build_children(
+            &ctx,
+            "sequential",
+            params.jobs,
+            params.forward_args.as_ref(),
+            &pool,
+        )?;
+        let finally = params
+            .finally
             .into_iter()
-            .map(|job_name| pool.build(ctx.clone(), job_name, Agent::Job))
+            .map(|job_name| pool.build(ctx.without_args(), job_name, Agent::Job))
             .collect::<JfResult<Vec<Job>>>()?;
         Ok(Self {
             ctx: ctx.new_span("sequential"),
+            job_names,
             jobs: jobs.into(),
+            continue_on_error: params.continue_on_error,
+            finally: finally.into(),
             canceller: Canceller::new(),
-            handle: Arc::new(Mutex::new(None)),
+            current_job: Arc::new(Mutex::new(None)),
+            handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         })
     }
@@ -57,9 +129,13 @@ impl Bunshin for Sequential {
     async fn bunshin(&self) -> Self {
         Self {
             ctx: self.ctx.clone(),
+            job_names: self.job_names.clone(),
             jobs: self.jobs.clone().into_inner().bunshin().await.into(),
+            continue_on_error: self.continue_on_error,
+            finally: self.finally.clone().into_inner().bunshin().await.into(),
             canceller: Canceller::new(),
-            handle: Arc::new(Mutex::new(None)),
+            current_job: Arc::new(Mutex::new(None)),
+            handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         }
     }
@@ -78,50 +154,135 @@ impl Runner for Sequential {
         let mut logger = self.ctx.logger();
         logger.debug("Sequential starting...").await?;
         let handle: JfHandle = tokio::spawn({
+            let ctx = self.ctx.clone();
+            let job_names = self.job_names.clone();
             let mut jobs = self.jobs.clone().into_inner();
+            let continue_on_error = self.continue_on_error;
+            let finally = self.finally.clone().into_inner();
             let canceller = self.canceller.clone();
-            let job = jobs[0].set_canceller(canceller.clone()).start().await?; // start first job immediately
+            let first = jobs[0].set_canceller(canceller.clone()).start().await; // start first job immediately
+            let current_job = self.current_job.clone();
+            if let Ok(job) = &first {
+                current_job.lock().await.replace(Current::Job(job.clone()));
+            }
             let finish_notify = self.finish_notify.clone();
 
             async move {
-                job.join().await?;
-                for mut job in jobs.into_iter().skip(1) {
-                    //TODO: remove this statement
-                    if canceller.is_canceled() {
-                        job.cancel().await?;
-                        break;
+                let result = async {
+                    let mut statuses = Vec::new();
+                    let mut first = Some(first);
+                    // `finally` still runs if a job fails to start
+                    let mut start_error = None;
+                    for mut job in jobs {
+                        let started = match first.take() {
+                            Some(started) => started,
+                            None => {
+                                if canceller.is_canceled() {
+                                    break;
+                                }
+                                let started = job.set_canceller(canceller.clone()).start().await;
+                                if let Ok(job) = &started {
+                                    current_job.lock().await.replace(Current::Job(job.clone()));
+                                    if canceller.is_canceled() {
+                                        job.cancel().await?; // cancelled while starting
+                                    }
+                                }
+                                started
+                            }
+                        };
+                        let job = match started {
+                            Ok(job) => job,
+                            Err(e) => {
+                                start_error = Some(e);
+                                break;
+                            }
+                        };
+                        let status = job.join().await?;
+                        statuses.push(status);
+                        if status.is_failed() && !continue_on_error {
+                            break;
+                        }
+                    }
+
+                    let mut finally_status = JoinStatus::Succeed;
+                    for job in finally {
+                        // the rest of `finally` still runs if one of them fails to start
+                        let job = match job.start().await {
+                            Ok(job) => job,
+                            Err(e) => {
+                                start_error.get_or_insert(e);
+                                continue;
+                            }
+                        };
+                        {
+                            let mut current = current_job.lock().await;
+                            if canceller.is_canceled() {
+                                cancel_after_grace_period(job.clone(), ctx.grace_period());
+                            }
+                            current.replace(Current::Finally(job.clone()));
+                        }
+                        let status = job.join().await?;
+                        if finally_status.is_succeed() {
+                            finally_status = status;
+                        }
+                    }
+
+                    if let Some(e) = start_error {
+                        return Err(e);
+                    }
+
+                    if continue_on_error {
+                        let results = job_names.iter().zip(&statuses);
+                        super::summarize_failures(&ctx, "sequential", job_names.len(), results)
+                            .await?;
                     }
-                    let status = job
-                        .set_canceller(canceller.clone())
-                        .start()
-                        .await?
-                        .join()
-                        .await?;
-                    if status.is_failed() {
-                        finish_notify.notify();
-                        return Ok(JoinStatus::Failed);
+                    // failure of `jobs` takes precedence over the one of `finally`
+                    if let Some(status) = statuses.into_iter().find(JoinStatus::is_failed) {
+                        return Ok(status);
                     }
+                    if canceller.is_canceled() {
+                        return Ok(JoinStatus::Failed(Failure::Cancelled));
+                    }
+                    Ok(finally_status)
                 }
+                .await;
+                // `join` waits for the notification even if the task failed
+                current_job.lock().await.take();
                 finish_notify.notify();
-                Ok(JoinStatus::Succeed)
+                result
             }
         });
-        self.handle.lock().await.replace(handle);
+        self.handle.set(handle).await;
         logger.debug("Sequential started").await?;
         Ok(self.clone())
     }
 
     async fn cancel(&self) -> JfResult<Self> {
+        let current = self.current_job.lock().await;
+        let first_cancel = !self.canceller.is_canceled();
         self.canceller.cancel();
+        match current.as_ref() {
+            Some(Current::Job(job)) => {
+                job.cancel().await?;
+            }
+            // `finally` is given the grace period to clean up
+            Some(Current::Finally(job)) if first_cancel => {
+                cancel_after_grace_period(job.clone(), self.ctx.grace_period());
+            }
+            _ => {}
+        }
         Ok(self.clone())
     }
 
     async fn join(&self) -> JfResult<JoinStatus> {
-        self.finish_notify.wait().await;
-        match self.handle.lock().await.deref_mut() {
-            Some(handle) => handle.await?,
-            None => Ok(JoinStatus::Succeed), // not started yet
+        self.handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        if let Some(current) = self.current_job.lock().await.as_ref() {
+            current.job().signal(signal).await?;
         }
+        Ok(())
     }
 
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
@@ -1,6 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 use crate::util::testutil::*;
 
+use std::time::Duration;
+
+use crate::jobdef::Jobdef;
+
 use super::*;
 
 impl TryAsyncFixture for Sequential {
@@ -8,6 +12,7 @@ impl TryAsyncFixture for Sequential {
     async fn try_async_fixture() -> JfResult<Self> {
         let params = SequentialParams {
             jobs: vec!["fast".into(), "fast".into()],
+            ..Default::default()
         };
         Sequential::new(
             Ctx::async_fixture().await,
@@ -23,7 +28,10 @@ fn invalid_new_with_empty_job() -> JfResult<()> {
     async_test(
         #[coverage(off)]
         async {
-            let params = SequentialParams { jobs: vec![] };
+            let params = SequentialParams {
+                jobs: vec![],
+                ..Default::default()
+            };
             let must_faile = Sequential::new(
                 Ctx::async_fixture().await,
                 params,
@@ -36,6 +44,39 @@ fn invalid_new_with_empty_job() -> JfResult<()> {
     )
 }
 
+#[test]
+#[coverage(off)]
+fn new_with_forward_args() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let ctx = Ctx::async_fixture().await.with_args(vec!["a".into()]);
+            let params = SequentialParams {
+                jobs: vec!["fast".into(), "fast".into()],
+                ..Default::default()
+            };
+            let must_fail = Sequential::new(ctx.clone(), params, TryFixture::try_fixture()?);
+            assert!(must_fail.is_err());
+
+            let params = SequentialParams {
+                jobs: vec!["fast".into()],
+                forward_args: Some("unknown".into()),
+                ..Default::default()
+            };
+            let must_fail = Sequential::new(ctx.clone(), params, TryFixture::try_fixture()?);
+            assert!(must_fail.is_err());
+
+            let params = SequentialParams {
+                jobs: vec!["fast".into()],
+                forward_args: Some("fast".into()),
+                ..Default::default()
+            };
+            assert!(Sequential::new(ctx, params, TryFixture::try_fixture()?).is_ok());
+            Ok(())
+        },
+    )
+}
+
 #[test]
 #[coverage(off)]
 fn invalid_new_with_unknown_job() -> JfResult<()> {
@@ -44,6 +85,7 @@ fn invalid_new_with_unknown_job() -> JfResult<()> {
         async {
             let params = SequentialParams {
                 jobs: vec!["unknown".into()],
+                ..Default::default()
             };
             let must_fail = Sequential::new(
                 Ctx::async_fixture().await,
@@ -157,3 +199,151 @@ fn is_finished_not_yet_started() -> JfResult<()> {
         },
     )
 }
+
+#[coverage(off)]
+async fn sequential_with(
+    jobs: &[&str],
+    continue_on_error: bool,
+    finally: &[&str],
+) -> JfResult<Sequential> {
+    let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    };
+    let pool = JobdefPool::new(vec![
+        jobdef(
+            "fast",
+            "mode = \"mock\"\neach_sleep_time = 1\nsleep_count = 1",
+        )?,
+        jobdef("fail", "command = \"sh\"\nargs = [\"-c\", \"exit 2\"]")?,
+        jobdef("sleep", "command = \"sleep\"\nargs = [\"10\"]")?,
+        jobdef("missing", "command = \"jf-missing-command\"")?,
+    ]);
+    let to_vec = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+    let params = SequentialParams {
+        jobs: to_vec(jobs),
+        continue_on_error,
+        finally: to_vec(finally),
+        ..Default::default()
+    };
+    Sequential::new(Ctx::async_fixture().await, params, pool)
+}
+
+#[test]
+#[coverage(off)]
+fn stop_on_error() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["fail", "fast"], false, &["fast"]).await?;
+            let status = s.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(2)));
+            s.jobs.read()[1].as_mock().assert_is_started_eq(false);
+            s.finally.read()[0].as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn continue_on_error() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["fail", "fast"], true, &[]).await?;
+            let status = s.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(2)));
+            s.jobs.read()[1].as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn finally_after_cancel() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["sleep", "fast"], true, &["fast"]).await?;
+            let status = s.start().await?.cancel().await?.join().await?;
+            assert!(status.is_failed());
+            s.jobs.read()[1].as_mock().assert_is_started_eq(false);
+            s.finally.read()[0]
+                .as_mock()
+                .assert_is_started_eq(true)
+                .assert_is_cancelled_eq(false);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn finally_failed() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["fast"], false, &["fail", "fast"]).await?;
+            let status = s.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(2)));
+            s.finally.read()[1].as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn finally_cancelled_after_grace_period() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let mut s = sequential_with(&["fast"], false, &["sleep"]).await?;
+            s.ctx = s.ctx.with_grace_period(Duration::from_millis(100));
+            // `sleep` in `finally` is cancelled after the grace period instead of 10 seconds
+            let status = tokio::time::timeout(Duration::from_secs(5), async {
+                s.start().await?.cancel().await?.join().await
+            })
+            .await
+            .map_err(|_| "cancelling finally timed out".into_jf_error())??;
+            assert_eq!(status, JoinStatus::Failed(Failure::Cancelled));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn finally_after_start_error() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["missing", "fast"], false, &["fast"]).await?;
+            assert!(s.start().await?.join().await.is_err());
+            s.jobs.read()[1].as_mock().assert_is_started_eq(false);
+            s.finally.read()[0].as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn finally_start_error() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let s = sequential_with(&["fast"], false, &["missing", "fast"]).await?;
+            let result = tokio::time::timeout(Duration::from_secs(5), async {
+                s.start().await?.join().await
+            })
+            .await
+            .map_err(|_| "joining sequential timed out".into_jf_error())?;
+            assert!(result.is_err());
+            assert!(s.is_finished().await?);
+            s.finally.read()[1].as_mock().assert_is_started_eq(true);
+            Ok(())
+        },
+    )
+}
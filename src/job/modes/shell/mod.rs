@@ -2,16 +2,40 @@
 #[cfg(test)]
 mod tests;
 
+use std::{collections::HashMap, path::PathBuf};
+
 use crate::{
     ctx::Ctx,
     job::{canceller::Canceller, join_status::JoinStatus, runner::*, Job},
-    util::{error::JfResult, ReadOnly},
+    util::{error::JfResult, template, ReadOnly},
 };
 
 #[derive(Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(Default))]
 pub struct ShellParams {
     pub script: String,
     pub args: Option<Vec<String>>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub env_file: Option<PathBuf>,
+    #[serde(default)]
+    pub clear_env: bool,
+    pub cwd: Option<PathBuf>,
+}
+
+impl ShellParams {
+    /// substitute `{{name}}` in `script` and `args`
+    pub fn render(&self, vars: &HashMap<String, String>) -> JfResult<Self> {
+        Ok(Self {
+            script: template::render(&self.script, vars)?,
+            args: self
+                .args
+                .as_ref()
+                .map(|args| template::render_all(args, vars))
+                .transpose()?,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -25,11 +49,20 @@ impl Shell {
     pub fn new(ctx: Ctx, params: ShellParams) -> Self {
         let mut args = params.args.clone().unwrap_or_default();
         args.extend(vec!["-c".to_string(), params.script.clone()]);
+        if !ctx.args().is_empty() {
+            // `sh -c <script> <$0> <$1>...`
+            args.push("jf".to_string());
+            args.extend(ctx.args().iter().cloned());
+        }
         let command = super::Command::new(
-            ctx.clone(),
+            ctx.without_args(),
             super::CommandParams {
                 command: "sh".to_string(),
                 args,
+                env: params.env.clone(),
+                env_file: params.env_file.clone(),
+                clear_env: params.clear_env,
+                cwd: params.cwd.clone(),
             },
         );
         Self {
@@ -77,6 +110,10 @@ impl Runner for Shell {
         self.command.join().await
     }
 
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.command.signal(signal).await
+    }
+
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
         self.command.set_canceller(canceller);
         self.clone()
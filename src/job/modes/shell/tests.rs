@@ -8,6 +8,7 @@ impl AsyncFixture for Shell {
         let params = ShellParams {
             script: "echo hello".to_string(),
             args: None,
+            ..Default::default()
         };
         Shell::new(Ctx::async_fixture().await, params)
     }
@@ -19,7 +20,11 @@ fn run_without_blocking() -> JfResult<()> {
     async_test(
         #[coverage(off)]
         async {
-            let shell = Shell::async_fixture().await;
+            let params = ShellParams {
+                script: "sleep 1; echo hello".to_string(),
+                ..Default::default()
+            };
+            let shell = Shell::new(Ctx::async_fixture().await, params);
             shell.start().await?;
             assert!(!shell.is_finished().await?);
             assert!(!shell.command.is_finished().await?);
@@ -51,7 +56,7 @@ fn cancel() -> JfResult<()> {
         #[coverage(off)]
         async {
             let shell = Shell::async_fixture().await;
-            shell.start().await?.cancel().await?;
+            shell.start().await?.cancel().await?.join().await?;
             assert!(shell.is_finished().await?);
             assert!(shell.command.is_finished().await?);
             Ok(())
@@ -66,7 +71,7 @@ fn bunshin() -> JfResult<()> {
         #[coverage(off)]
         async {
             let origin = Shell::async_fixture().await;
-            origin.start().await?.cancel().await?;
+            origin.start().await?.cancel().await?.join().await?;
             assert!(origin.is_finished().await?);
             let bunshin = origin.bunshin().await;
             assert!(!bunshin.is_finished().await?);
@@ -87,3 +92,24 @@ fn is_finished_not_yet_started() -> JfResult<()> {
         },
     )
 }
+
+#[test]
+#[coverage(off)]
+fn join_with_args() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let ctx = Ctx::async_fixture()
+                .await
+                .with_args(vec!["a".into(), "b c".into()]);
+            let params = ShellParams {
+                script: r#"test "$#" = 2 && test "$1" = a && test "$2" = "b c""#.to_string(),
+                ..Default::default()
+            };
+            let shell = Shell::new(ctx, params);
+            shell.start().await?;
+            assert!(shell.join().await?.is_succeed());
+            Ok(())
+        },
+    )
+}
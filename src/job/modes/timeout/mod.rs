@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MPL-2.0
+#[cfg(test)]
+mod tests;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    ctx::Ctx,
+    job::{
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
+    },
+    util::{duration, error::JfResult},
+};
+
+/// Job which is cancelled if it does not finish within `duration`
+#[derive(Clone)]
+pub struct Timeout {
+    ctx: Ctx,
+    duration: Duration,
+    job: Box<Job>,
+    canceller: Canceller,
+    handle: TaskHandle,
+    finish_notify: Arc<FinishNotify>,
+}
+
+impl Timeout {
+    pub fn new(ctx: Ctx, duration: Duration, job: Job) -> Self {
+        Self {
+            ctx: ctx.new_span("timeout"),
+            duration,
+            job: Box::new(job),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Bunshin for Timeout {
+    async fn bunshin(&self) -> Self {
+        Self {
+            ctx: self.ctx.clone(),
+            duration: self.duration,
+            job: Box::new(self.job.bunshin().await),
+            canceller: Canceller::new(),
+            handle: TaskHandle::default(),
+            finish_notify: FinishNotify::new_arc(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for Timeout {
+    async fn is_finished(&self) -> JfResult<bool> {
+        Ok(self.finish_notify.is_finished())
+    }
+}
+
+#[async_trait::async_trait]
+impl Runner for Timeout {
+    async fn start(&self) -> JfResult<Self> {
+        let mut logger = self.ctx.logger();
+        logger.debug("Timeout starting...").await?;
+        let job = self.job.start().await?;
+        let handle: JfHandle = tokio::spawn({
+            let mut logger = self.ctx.logger();
+            let duration = self.duration;
+            let canceller = self.canceller.clone();
+            let finish_notify = self.finish_notify.clone();
+
+            async move {
+                let status = tokio::select! {
+                    status = job.join() => status?,
+                    _ = tokio::time::sleep(duration) => {
+                        job.cancel().await?;
+                        let status = job.join().await?; // wait for termination
+                        if canceller.is_canceled() {
+                            status
+                        } else {
+                            logger
+                                .error(format!("timed out after {}", duration::format(duration)))
+                                .await?;
+                            JoinStatus::Failed(Failure::TimedOut)
+                        }
+                    }
+                };
+                finish_notify.notify();
+                Ok(status)
+            }
+        });
+        self.handle.set(handle).await;
+        logger.debug("Timeout started").await?;
+        Ok(self.clone())
+    }
+
+    async fn cancel(&self) -> JfResult<Self> {
+        self.canceller.cancel();
+        self.job.cancel().await?;
+        Ok(self.clone())
+    }
+
+    async fn join(&self) -> JfResult<JoinStatus> {
+        self.handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.job.signal(signal).await
+    }
+
+    fn set_canceller(&mut self, canceller: Canceller) -> Self {
+        self.canceller = canceller;
+        self.clone()
+    }
+}
+
+impl From<Timeout> for Job {
+    fn from(value: Timeout) -> Self {
+        Self::Timeout(value)
+    }
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::time::Instant;
+
+use crate::job::modes::{Command, CommandParams};
+use crate::util::testutil::*;
+
+use super::*;
+
+#[coverage(off)]
+async fn sh(script: &str) -> Job {
+    let params = CommandParams {
+        command: "sh".into(),
+        args: vec!["-c".into(), script.into()],
+        ..Default::default()
+    };
+    Command::new(Ctx::async_fixture().await, params).into()
+}
+
+#[test]
+#[coverage(off)]
+fn finish_in_time() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let timeout = Timeout::new(
+                Ctx::async_fixture().await,
+                Duration::from_secs(10),
+                sh("exit 3").await,
+            );
+            let status = timeout.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::Code(3)));
+            assert!(timeout.is_finished().await?);
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn timed_out() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let timeout = Timeout::new(
+                Ctx::async_fixture().await,
+                Duration::from_millis(100),
+                sh("sleep 10").await,
+            );
+            let begin = Instant::now();
+            let status = timeout.start().await?.join().await?;
+            assert_eq!(status, JoinStatus::Failed(Failure::TimedOut));
+            assert!(begin.elapsed() < Duration::from_secs(5));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn cancel() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let timeout = Timeout::new(
+                Ctx::async_fixture().await,
+                Duration::from_secs(10),
+                sh("sleep 10").await,
+            );
+            timeout.start().await?;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let status = timeout.cancel().await?.join().await?;
+            assert!(status.is_failed());
+            assert_ne!(status, JoinStatus::Failed(Failure::TimedOut));
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn bunshin() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let origin = Timeout::new(
+                Ctx::async_fixture().await,
+                Duration::from_secs(10),
+                sh("exit 0").await,
+            );
+            origin.start().await?.join().await?;
+            assert!(origin.is_finished().await?);
+            let bunshin = origin.bunshin().await;
+            assert!(!bunshin.is_finished().await?);
+            assert!(bunshin.start().await?.join().await?.is_succeed());
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
+use notify::RecursiveMode;
+
+use crate::util::error::JfResult;
+
+const GLOB_CHARS: &[char] = &['*', '?', '[', '{'];
+
+/// Decide which paths of `watch_list` trigger a restart
+///
+/// Globs are matched against each changed path instead of being expanded once,
+/// so files created after the watcher started are also watched.
+pub struct WatchFilter {
+    targets: GlobSet,
+    ignore: Gitignore,
+    gitignore: bool,
+    base: PathBuf,
+    roots: Vec<(PathBuf, RecursiveMode)>,
+}
+
+/// resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// whether `.gitignore` files in directories of `path` ignore it, like git
+///
+/// Deeper files take precedence, and files above the repository (directory with `.git`) are not read.
+fn is_gitignored(path: &Path) -> bool {
+    let is_dir = path.is_dir();
+    for dir in path.ancestors().skip(1) {
+        let file = dir.join(".gitignore");
+        if file.is_file() {
+            let (gitignore, _) = Gitignore::new(&file); // invalid lines are skipped like git
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    false
+}
+
+fn glob(pattern: &str) -> JfResult<globset::Glob> {
+    Ok(GlobBuilder::new(pattern).literal_separator(true).build()?)
+}
+
+impl WatchFilter {
+    /// `base` is the directory which relative patterns belong to
+    ///
+    /// `.gitignore` files are read for each changed path, so the ones in subdirectories
+    /// and parent directories of `base` also apply.
+    pub fn new(
+        base: &Path,
+        watch_list: &[String],
+        ignore: &[String],
+        gitignore: bool,
+    ) -> JfResult<Self> {
+        let base = &std::path::absolute(base)?;
+        let mut targets = GlobSetBuilder::new();
+        let mut roots: Vec<(PathBuf, RecursiveMode)> = Vec::new();
+        for item in watch_list {
+            let path = normalize(&base.join(item));
+            let components = path.components().collect::<Vec<_>>();
+            let first_glob = components
+                .iter()
+                .position(|c| c.as_os_str().to_string_lossy().contains(GLOB_CHARS));
+            let pattern = path.to_string_lossy();
+            let root = match first_glob {
+                Some(index) => {
+                    targets.add(glob(&pattern)?);
+                    let rest = &components[index..];
+                    let recursive = rest.len() > 1 || pattern.contains("**");
+                    let mode = if recursive {
+                        RecursiveMode::Recursive
+                    } else {
+                        RecursiveMode::NonRecursive
+                    };
+                    (components[..index].iter().collect(), mode)
+                }
+                None if path.is_dir() => {
+                    let escaped = globset::escape(&pattern);
+                    targets.add(glob(&escaped)?);
+                    targets.add(glob(&format!("{escaped}/**"))?);
+                    (path, RecursiveMode::Recursive)
+                }
+                None => {
+                    targets.add(glob(&globset::escape(&pattern))?);
+                    // parent is watched to follow editors which replace the file on save
+                    let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                    (parent, RecursiveMode::NonRecursive)
+                }
+            };
+            if !root.0.exists() {
+                continue;
+            }
+            match roots.iter_mut().find(|(path, _)| *path == root.0) {
+                Some(found) if root.1 == RecursiveMode::Recursive => found.1 = root.1,
+                Some(_) => {}
+                None => roots.push(root),
+            }
+        }
+
+        let mut builder = GitignoreBuilder::new(base);
+        for pattern in ignore {
+            builder.add_line(None, pattern)?;
+        }
+
+        Ok(Self {
+            targets: targets.build()?,
+            ignore: builder.build()?,
+            gitignore,
+            base: base.to_path_buf(),
+            roots,
+        })
+    }
+
+    /// existing directories (or files) to register to the watcher
+    pub fn roots(&self) -> &Vec<(PathBuf, RecursiveMode)> {
+        &self.roots
+    }
+
+    pub fn is_target(&self, path: &Path) -> bool {
+        if !self.targets.is_match(path) {
+            return false;
+        }
+        if self.gitignore && is_gitignored(path) {
+            return false;
+        }
+        if !path.starts_with(&self.base) {
+            return true; // `ignore` is relative to `base`
+        }
+        !self
+            .ignore
+            .matched_path_or_any_parents(path, path.is_dir())
+            .is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn base() -> PathBuf {
+        std::env::current_dir().unwrap()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn roots() -> JfResult<()> {
+        let filter = WatchFilter::new(
+            &base(),
+            &[
+                "./src/**/*.rs".into(),
+                "src/job/*".into(),
+                "tests".into(),
+                "Cargo.toml".into(),
+                "unknown/*.rs".into(),
+            ],
+            &[],
+            false,
+        )?;
+        assert_eq!(
+            filter.roots(),
+            &vec![
+                (base().join("src"), RecursiveMode::Recursive),
+                (base().join("src/job"), RecursiveMode::NonRecursive),
+                (base().join("tests"), RecursiveMode::Recursive),
+                (base(), RecursiveMode::NonRecursive),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn is_target() -> JfResult<()> {
+        let filter = WatchFilter::new(
+            &base(),
+            &["src/**".into(), "tests".into(), "Cargo.toml".into()],
+            &["*.swp".into(), "src/generated/**".into()],
+            true,
+        )?;
+        assert!(filter.is_target(&base().join("src/new/file.rs")));
+        assert!(filter.is_target(&base().join("tests/fixtures/jf.toml")));
+        assert!(filter.is_target(&base().join("Cargo.toml")));
+        assert!(!filter.is_target(&base().join("Cargo.lock"))); // not in watch_list
+        assert!(!filter.is_target(&base().join("src/.main.rs.swp"))); // ignore
+        assert!(!filter.is_target(&base().join("src/generated/a.rs"))); // ignore
+        assert!(!filter.is_target(&base().join("src/lib.rlib"))); // .gitignore
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn without_gitignore() -> JfResult<()> {
+        let filter = WatchFilter::new(&base(), &["src/**".into()], &[], false)?;
+        assert!(filter.is_target(&base().join("src/lib.rlib")));
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn nested_gitignore() -> JfResult<()> {
+        let repo = std::env::temp_dir().join(format!("jf-gitignore-{}", std::process::id()));
+        let base = repo.join("pkg");
+        std::fs::create_dir_all(repo.join(".git"))?;
+        std::fs::create_dir_all(base.join("sub"))?;
+        std::fs::write(repo.join(".gitignore"), "*.gen\n")?;
+        std::fs::write(base.join(".gitignore"), "*.log\n")?;
+        std::fs::write(base.join("sub/.gitignore"), "*.tmp\n!keep.log\n")?;
+        let filter = WatchFilter::new(&base, &["**".into()], &[], true)?;
+        assert!(filter.is_target(&base.join("a.rs")));
+        assert!(!filter.is_target(&base.join("a.gen"))); // parent of `base`
+        assert!(!filter.is_target(&base.join("a.log")));
+        assert!(!filter.is_target(&base.join("sub/a.tmp"))); // subdirectory
+        assert!(filter.is_target(&base.join("sub/keep.log"))); // deeper one wins
+        std::fs::remove_dir_all(repo)?;
+        Ok(())
+    }
+}
@@ -1,46 +1,173 @@
 // SPDX-License-Identifier: MPL-2.0
+mod filter;
 #[cfg(test)]
 mod tests;
 mod watcher;
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use tokio::sync::Mutex;
 
 use crate::{
     ctx::Ctx,
     job::{
-        canceller::Canceller, finish_notify::FinishNotify, join_status::JoinStatus, runner::*, Job,
+        canceller::Canceller,
+        finish_notify::FinishNotify,
+        join_status::{Failure, JoinStatus},
+        runner::*,
+        task_handle::TaskHandle,
+        Job,
     },
     jobdef::{Agent, JobdefPool},
-    util::error::JfResult,
+    util::{error::JfResult, template},
 };
 
+/// What to do with the running job when a change is detected
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum OnChange {
+    /// cancel the running job and start it again
+    #[default]
+    #[serde(rename = "restart")]
+    Restart,
+    /// let the running job finish, then start it once more
+    #[serde(rename = "queue")]
+    Queue,
+    /// skip changes while the job is running
+    #[serde(rename = "ignore_while_running")]
+    IgnoreWhileRunning,
+    /// send SIGHUP to the running job instead of restarting it
+    #[serde(rename = "signal")]
+    Signal,
+}
+
+/// How to detect changes of files
+#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, Debug))]
+pub enum WatchBackend {
+    /// notification of OS (e.g. inotify)
+    #[default]
+    #[serde(rename = "native")]
+    Native,
+    /// scan files every `poll_interval_ms`, which also works on network filesystems
+    #[serde(rename = "poll")]
+    Poll,
+}
+
 #[derive(Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(Default))]
 pub struct WatchParams {
     pub job: String,
     pub watch_list: Vec<String>,
+    /// default working directory of child jobs
+    pub cwd: Option<PathBuf>,
+    /// wait for no more changes before restarting
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// patterns in `.gitignore` syntax which never trigger a restart
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// also skip files listed in `.gitignore`
+    #[serde(default = "default_gitignore")]
+    pub gitignore: bool,
+    #[serde(default)]
+    pub on_change: OnChange,
+    #[serde(default)]
+    pub backend: WatchBackend,
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// directory which relative `watch_list` and `.gitignore` belong to (`cwd` or dir of cfg file)
+    #[serde(skip)]
+    pub base: Option<PathBuf>,
+}
+
+impl WatchParams {
+    /// params with default options
+    pub fn new(job: String, watch_list: Vec<String>) -> Self {
+        Self {
+            job,
+            watch_list,
+            cwd: None,
+            debounce_ms: default_debounce_ms(),
+            ignore: Vec::new(),
+            gitignore: default_gitignore(),
+            on_change: OnChange::default(),
+            backend: WatchBackend::default(),
+            poll_interval_ms: default_poll_interval_ms(),
+            base: None,
+        }
+    }
+}
+
+fn default_debounce_ms() -> u64 {
+    100
+}
+
+fn default_gitignore() -> bool {
+    true
+}
+
+fn default_poll_interval_ms() -> u64 {
+    500
+}
+
+impl WatchParams {
+    /// substitute `{{name}}` in `job` and `watch_list`
+    pub fn render(&self, vars: &HashMap<String, String>) -> JfResult<Self> {
+        Ok(Self {
+            job: template::render(&self.job, vars)?,
+            watch_list: template::render_all(&self.watch_list, vars)?,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Clone)]
 pub struct Watch {
     ctx: Ctx,
+    /// to rebuild the job with changed files on restart
+    job_ctx: Ctx,
+    job_name: String,
+    pool: JobdefPool,
     job: Arc<Mutex<Job>>,
+    /// directory which `watch_list` and `.gitignore` belong to (current directory if `None`)
+    base: Option<PathBuf>,
     watch_list: Vec<String>,
+    ignore: Vec<String>,
+    gitignore: bool,
+    debounce: Duration,
+    on_change: OnChange,
+    /// interval of polling, or `None` for the native backend
+    poll_interval: Option<Duration>,
     canceller: Canceller,
-    handle: Arc<Mutex<Option<JfHandle>>>,
+    handle: TaskHandle,
     finish_notify: Arc<FinishNotify>,
 }
 
 impl Watch {
     pub fn new(ctx: Ctx, params: WatchParams, pool: JobdefPool) -> JfResult<Self> {
-        let job = pool.build(ctx.clone(), params.job, Agent::Job)?;
+        let base = params.base.clone().or_else(|| ctx.cwd().cloned());
+        let ctx = ctx.inherit_cwd(params.cwd);
+        let job = pool.build(ctx.clone(), params.job.clone(), Agent::Job)?;
+        let poll_interval = match (params.backend, ctx.watch_poll()) {
+            (WatchBackend::Native, false) => None,
+            _ => Some(Duration::from_millis(params.poll_interval_ms)),
+        };
         Ok(Self {
             ctx: ctx.new_span("watch"),
+            job_ctx: ctx,
+            job_name: params.job,
+            pool,
             job: Arc::new(Mutex::new(job)),
+            base,
             watch_list: params.watch_list,
+            ignore: params.ignore,
+            gitignore: params.gitignore,
+            debounce: Duration::from_millis(params.debounce_ms),
+            on_change: params.on_change,
+            poll_interval,
             canceller: Canceller::new(),
-            handle: Arc::new(Mutex::new(None)),
+            handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         })
     }
@@ -51,10 +178,19 @@ impl Bunshin for Watch {
     async fn bunshin(&self) -> Self {
         Self {
             ctx: self.ctx.clone(),
+            job_ctx: self.job_ctx.clone(),
+            job_name: self.job_name.clone(),
+            pool: self.pool.clone(),
             job: Arc::new(Mutex::new(self.job.lock().await.bunshin().await)),
+            base: self.base.clone(),
             watch_list: self.watch_list.clone(),
+            ignore: self.ignore.clone(),
+            gitignore: self.gitignore,
+            debounce: self.debounce,
+            on_change: self.on_change,
+            poll_interval: self.poll_interval,
             canceller: Canceller::new(),
-            handle: Arc::new(Mutex::new(None)),
+            handle: TaskHandle::default(),
             finish_notify: FinishNotify::new_arc(),
         }
     }
@@ -74,28 +210,90 @@ impl Runner for Watch {
         logger.debug("Watch starting...").await?;
         let handle = tokio::spawn({
             let finish_notify = self.finish_notify.clone();
+            let base = self.base.clone();
             let watch_list = self.watch_list.clone();
+            let ignore = self.ignore.clone();
+            let gitignore = self.gitignore;
+            let debounce = self.debounce;
+            let on_change = self.on_change;
+            let poll_interval = self.poll_interval;
+            let job_ctx = self.job_ctx.clone();
+            let job_name = self.job_name.clone();
+            let pool = self.pool.clone();
+            let mut logger = self.ctx.logger();
             let job = self.job.clone();
             let canceller = self.canceller.clone();
             job.lock().await.start().await?;
 
             async move {
                 loop {
-                    watcher::JfWatcher::new(&watch_list, canceller.clone())?
-                        .wait()
-                        .await?;
+                    // rebuilt every time to pick up changes of `.gitignore`
+                    let base = match &base {
+                        Some(base) => std::path::absolute(base)?,
+                        None => std::env::current_dir()?,
+                    };
+                    let filter = filter::WatchFilter::new(&base, &watch_list, &ignore, gitignore)?;
+                    let changed = watcher::JfWatcher::new(
+                        filter,
+                        debounce,
+                        poll_interval,
+                        canceller.clone(),
+                    )?
+                    .wait()
+                    .await?;
 
-                    job.lock().await.cancel().await?.join().await?;
+                    if !canceller.is_canceled() {
+                        // not locked while joining to be cancelled
+                        let current = job.lock().await.clone();
+                        let is_running = !current.is_finished().await?;
+                        match on_change {
+                            OnChange::Restart => {
+                                current.cancel().await?.join().await?;
+                            }
+                            OnChange::Queue => {
+                                if is_running {
+                                    logger.info("restart queued until the job finishes").await?;
+                                }
+                                current.join().await?;
+                            }
+                            OnChange::IgnoreWhileRunning if is_running => {
+                                logger
+                                    .info("change ignored while the job is running")
+                                    .await?;
+                                continue;
+                            }
+                            OnChange::Signal if is_running => {
+                                current.signal(libc::SIGHUP).await?;
+                                continue;
+                            }
+                            OnChange::IgnoreWhileRunning | OnChange::Signal => {}
+                        }
+                    }
                     if canceller.is_canceled() {
+                        job.lock().await.cancel().await?.join().await?;
                         finish_notify.notify();
-                        return Ok(JoinStatus::Failed);
+                        return Ok(JoinStatus::Failed(Failure::Cancelled));
                     }
 
-                    job.lock().await.reset().await?.start().await?;
+                    // relative to where the job runs
+                    let cwd = match pool.cwd(&job_ctx, &job_name) {
+                        Some(cwd) => std::path::absolute(cwd)?,
+                        None => std::env::current_dir()?,
+                    };
+                    let changed_files = changed
+                        .iter()
+                        .map(|path| path.strip_prefix(&cwd).unwrap_or(path))
+                        .map(|path| path.to_string_lossy().into_owned())
+                        .collect();
+                    let ctx = job_ctx.clone().with_changed_files(changed_files);
+                    let mut current = job.lock().await;
+                    // fresh pool to run dependencies (`depends_on`) again
+                    *current = pool.fresh().build(ctx, job_name.clone(), Agent::Job)?;
+                    current.start().await?;
                 }
             }
         });
-        self.handle.lock().await.replace(handle);
+        self.handle.set(handle).await;
         logger.debug("Watch started").await?;
         Ok(self.clone())
     }
@@ -107,12 +305,11 @@ impl Runner for Watch {
     }
 
     async fn join(&self) -> JfResult<JoinStatus> {
-        self.finish_notify.wait().await;
-        if let Some(handle) = self.handle.lock().await.deref_mut() {
-            return handle.await?;
-        } else {
-            return Ok(JoinStatus::Succeed);
-        }
+        self.handle.join_finished(&self.finish_notify).await
+    }
+
+    async fn signal(&self, signal: i32) -> JfResult<()> {
+        self.job.lock().await.signal(signal).await
     }
 
     fn set_canceller(&mut self, canceller: Canceller) -> Self {
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 use std::io::Write;
 
-use crate::util::testutil::*;
+use crate::util::{error::IntoJfError, testutil::*};
 
 use super::*;
 
@@ -19,6 +19,7 @@ impl Fixture for WatchParams {
         WatchParams {
             job: "fast".to_string(),
             watch_list: fixtures::watch_list(),
+            ..Default::default()
         }
     }
 }
@@ -43,6 +44,7 @@ fn invalid_new_with_unknown_job() -> JfResult<()> {
             let params = WatchParams {
                 job: "unknown".to_string(),
                 watch_list: fixtures::watch_list(),
+                ..Default::default()
             };
             assert!(Watch::new(
                 Ctx::async_fixture().await,
@@ -120,3 +122,324 @@ fn bunshin() -> JfResult<()> {
         },
     )
 }
+
+#[test]
+#[coverage(off)]
+fn watch_new_files_with_debounce() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dir = std::env::temp_dir().join(format!("jf-watch-{}", std::process::id()));
+            std::fs::create_dir_all(&dir)?;
+            let params = WatchParams {
+                job: "fast".to_string(),
+                watch_list: vec![format!("{}/**/*.txt", dir.display())],
+                ignore: vec!["*.swp".to_string()],
+                debounce_ms: 300,
+                ..Default::default()
+            };
+            let w = Watch::new(
+                Ctx::async_fixture().await,
+                params,
+                TryFixture::try_fixture()?,
+            )?;
+            w.start().await?;
+            let id = w.job.lock().await.as_mock().id();
+            tokio::time::sleep(Duration::from_millis(100)).await; // wait for the watcher to start
+
+            std::fs::create_dir_all(dir.join("sub"))?; // created after the watcher started
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            std::fs::File::create(dir.join("sub/file.swp"))?.write_all(b"")?;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            assert_eq!(id, w.job.lock().await.as_mock().id()); // ignored
+
+            for _ in 0..3 {
+                std::fs::File::create(dir.join("sub/file.txt"))?.write_all(b"")?;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            assert_eq!(id, w.job.lock().await.as_mock().id()); // debounced
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            assert_ne!(id, w.job.lock().await.as_mock().id());
+
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+/// watch a fresh directory and run a mock job which takes 500ms
+#[coverage(off)]
+async fn slow_watch(on_change: OnChange) -> JfResult<(Watch, PathBuf)> {
+    let dir = std::env::temp_dir().join(format!("jf-watch-{}-{on_change:?}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let pool = JobdefPool::new(vec![crate::jobdef::Jobdef::new(
+        "slow".into(),
+        toml::from_str("mode = \"mock\"\neach_sleep_time = 100\nsleep_count = 5")?,
+    )?]);
+    let params = WatchParams {
+        job: "slow".to_string(),
+        watch_list: vec![format!("{}/*", dir.display())],
+        on_change,
+        ..Default::default()
+    };
+    let w = Watch::new(Ctx::async_fixture().await, params, pool)?;
+    w.start().await?;
+    tokio::time::sleep(Duration::from_millis(100)).await; // wait for the watcher to start
+    Ok((w, dir))
+}
+
+#[coverage(off)]
+async fn touch_and_wait(dir: &std::path::Path, millis: u64) -> JfResult<()> {
+    std::fs::File::create(dir.join("file.txt"))?.write_all(b"")?;
+    tokio::time::sleep(Duration::from_millis(millis)).await;
+    Ok(())
+}
+
+#[test]
+#[coverage(off)]
+fn on_change_restart() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let (w, dir) = slow_watch(OnChange::Restart).await?;
+            let first = w.job.lock().await.as_mock().clone();
+            touch_and_wait(&dir, 100).await?;
+            first.assert_is_cancelled_eq(true);
+            w.job.lock().await.as_mock().assert_id_ne(first.id());
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn on_change_queue() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let (w, dir) = slow_watch(OnChange::Queue).await?;
+            let first = w.job.lock().await.as_mock().clone();
+            touch_and_wait(&dir, 100).await?;
+            w.job.lock().await.as_mock().assert_id_eq(first.id());
+            tokio::time::sleep(Duration::from_millis(500)).await; // first run finishes
+            first
+                .assert_is_cancelled_eq(false)
+                .assert_is_finished_eq(true);
+            w.job.lock().await.as_mock().assert_id_ne(first.id());
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+/// the watch loop and `cancel` join the same child job
+#[test]
+#[coverage(off)]
+fn on_change_queue_cancel_composite() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dir =
+                std::env::temp_dir().join(format!("jf-watch-{}-queue-cancel", std::process::id()));
+            std::fs::create_dir_all(&dir)?;
+            let pool = JobdefPool::new(vec![
+                crate::jobdef::Jobdef::new(
+                    "slow".into(),
+                    toml::from_str("mode = \"mock\"\neach_sleep_time = 100\nsleep_count = 5")?,
+                )?,
+                crate::jobdef::Jobdef::new(
+                    "steps".into(),
+                    toml::from_str("mode = \"sequential\"\njobs = [\"slow\", \"slow\"]")?,
+                )?,
+            ]);
+            let params = WatchParams {
+                job: "steps".to_string(),
+                watch_list: vec![format!("{}/*", dir.display())],
+                on_change: OnChange::Queue,
+                ..Default::default()
+            };
+            let w = Watch::new(Ctx::async_fixture().await, params, pool)?;
+            w.start().await?;
+            tokio::time::sleep(Duration::from_millis(100)).await; // wait for the watcher to start
+            touch_and_wait(&dir, 200).await?; // the loop is joining the running job
+            let status = tokio::time::timeout(Duration::from_secs(5), async {
+                w.cancel().await?;
+                w.join().await
+            })
+            .await
+            .map_err(|_| "cancelling queued watch timed out".into_jf_error())??;
+            assert!(status.is_failed());
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn on_change_ignore_while_running() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let (w, dir) = slow_watch(OnChange::IgnoreWhileRunning).await?;
+            let first = w.job.lock().await.as_mock().clone();
+            touch_and_wait(&dir, 600).await?; // first run finishes without restart
+            first
+                .assert_is_cancelled_eq(false)
+                .assert_is_finished_eq(true);
+            w.job.lock().await.as_mock().assert_id_eq(first.id());
+            touch_and_wait(&dir, 100).await?; // not running
+            w.job.lock().await.as_mock().assert_id_ne(first.id());
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn on_change_signal() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let (w, dir) = slow_watch(OnChange::Signal).await?;
+            let first = w.job.lock().await.as_mock().clone();
+            touch_and_wait(&dir, 100).await?;
+            first
+                .assert_last_signal_eq(libc::SIGHUP)
+                .assert_is_cancelled_eq(false);
+            w.job.lock().await.as_mock().assert_id_eq(first.id());
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn deserialize_on_change() -> JfResult<()> {
+    let params: WatchParams = toml::from_str(
+        r#"
+job = "test"
+watch_list = ["src/**"]
+on_change = "ignore_while_running"
+"#,
+    )?;
+    assert_eq!(params.on_change, OnChange::IgnoreWhileRunning);
+    assert_eq!(params.debounce_ms, 100);
+    assert!(params.gitignore);
+    let params: WatchParams = toml::from_str("job = \"test\"\nwatch_list = []")?;
+    assert_eq!(params.on_change, OnChange::Restart);
+    Ok(())
+}
+
+#[test]
+#[coverage(off)]
+fn changed_files() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dir = std::env::temp_dir().join(format!("jf-watch-{}-changed", std::process::id()));
+            std::fs::create_dir_all(dir.join("src"))?;
+            let output = dir.join("output");
+            let count = dir.join("count");
+            let script = format!(
+                r#"mode = "shell"
+script = 'echo "$JF_CHANGED_FILES|"{{{{changed_files}}}} > {}'
+cwd = "{}"
+depends_on = ["count"]"#,
+                output.display(),
+                dir.display()
+            );
+            let count_script = format!("mode = \"shell\"\nscript = 'echo >> {}'", count.display());
+            let pool = JobdefPool::new(vec![
+                crate::jobdef::Jobdef::new("report".into(), toml::from_str(&script)?)?,
+                crate::jobdef::Jobdef::new("count".into(), toml::from_str(&count_script)?)?,
+            ]);
+            let params = WatchParams {
+                job: "report".to_string(),
+                watch_list: vec![format!("{}/src/*", dir.display())],
+                debounce_ms: 200,
+                ..Default::default()
+            };
+            let w = Watch::new(Ctx::async_fixture().await, params, pool)?;
+            w.start().await?;
+            tokio::time::sleep(Duration::from_millis(100)).await; // wait for the watcher to start
+            assert_eq!(std::fs::read_to_string(&output)?, "|\n");
+
+            std::fs::File::create(dir.join("src/b.txt"))?.write_all(b"")?;
+            std::fs::File::create(dir.join("src/a $x.txt"))?.write_all(b"")?;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            // relative to `cwd` of the job, and kept as one word by the shell
+            assert_eq!(
+                std::fs::read_to_string(&output)?,
+                "src/a $x.txt\nsrc/b.txt|src/a $x.txt src/b.txt\n"
+            );
+            assert_eq!(std::fs::read_to_string(&count)?, "\n\n"); // `depends_on` runs again
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn poll_backend() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let dir = std::env::temp_dir().join(format!("jf-watch-{}-poll", std::process::id()));
+            std::fs::create_dir_all(&dir)?;
+            let params = WatchParams {
+                job: "fast".to_string(),
+                watch_list: vec![format!("{}/*", dir.display())],
+                backend: WatchBackend::Poll,
+                poll_interval_ms: 50,
+                ..Default::default()
+            };
+            let w = Watch::new(
+                Ctx::async_fixture().await,
+                params,
+                TryFixture::try_fixture()?,
+            )?;
+            assert_eq!(w.poll_interval, Some(Duration::from_millis(50)));
+            w.start().await?;
+            let id = w.job.lock().await.as_mock().id();
+            tokio::time::sleep(Duration::from_millis(100)).await; // wait for the first scan
+            std::fs::File::create(dir.join("file.txt"))?.write_all(b"")?;
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            w.job.lock().await.as_mock().assert_id_ne(id);
+            w.cancel().await?;
+            std::fs::remove_dir_all(dir)?;
+            Ok(())
+        },
+    )
+}
+
+#[test]
+#[coverage(off)]
+fn watch_poll_override() -> JfResult<()> {
+    async_test(
+        #[coverage(off)]
+        async {
+            let w = Watch::new(
+                Ctx::async_fixture().await.with_watch_poll(true),
+                Fixture::fixture(),
+                TryFixture::try_fixture()?,
+            )?;
+            assert!(w.poll_interval.is_some()); // even with `backend = "native"`
+            let params: WatchParams =
+                toml::from_str("job = \"test\"\nwatch_list = []\nbackend = \"poll\"")?;
+            assert_eq!(params.backend, WatchBackend::Poll);
+            assert_eq!(params.poll_interval_ms, 500);
+            Ok(())
+        },
+    )
+}
@@ -1,6 +1,13 @@
 // SPDX-License-Identifier: MPL-2.0
-use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use notify::{Config, EventKind, PollWatcher, RecommendedWatcher, Watcher};
 
+use super::filter::WatchFilter;
 use crate::{
     job::canceller::Canceller,
     util::{
@@ -12,44 +19,78 @@ use crate::{
 type NotifyPayload = Result<notify::Event, notify::Error>;
 
 pub struct JfWatcher {
-    _watcher: ReadOnly<RecommendedWatcher>, // not used but needed to keep the watcher alive
+    _watcher: ReadOnly<Box<dyn Watcher + Send + Sync>>, // not used but needed to keep the watcher alive
     rx: std::sync::mpsc::Receiver<NotifyPayload>,
+    filter: WatchFilter,
+    debounce: Duration,
     canceller: Canceller,
 }
 
 impl JfWatcher {
-    pub fn new(watch_list: &Vec<String>, canceller: Canceller) -> JfResult<Self> {
+    /// poll files every `poll_interval` if given, otherwise use the native backend of OS
+    pub fn new(
+        filter: WatchFilter,
+        debounce: Duration,
+        poll_interval: Option<Duration>,
+        canceller: Canceller,
+    ) -> JfResult<Self> {
         let (tx, rx) = std::sync::mpsc::channel();
-        let mut watcher = RecommendedWatcher::new(tx, Config::default())?;
+        let mut watcher: Box<dyn Watcher + Send + Sync> = match poll_interval {
+            Some(interval) => Box::new(PollWatcher::new(
+                tx,
+                Config::default().with_poll_interval(interval),
+            )?),
+            None => Box::new(RecommendedWatcher::new(tx, Config::default())?),
+        };
 
-        for watch_item in watch_list {
-            for entry in glob::glob(watch_item.as_str())? {
-                watcher.watch(entry?.as_path(), RecursiveMode::NonRecursive)?;
-            }
+        for (path, mode) in filter.roots() {
+            watcher.watch(path, *mode)?;
         }
 
         Ok(Self {
             _watcher: watcher.into(),
             rx,
+            filter,
+            debounce,
             canceller,
         })
     }
 
-    pub async fn wait(self) -> JfResult<()> {
+    /// wait until a target changed and no more changes came for `debounce`
+    ///
+    /// Returns every changed target collected meanwhile.
+    pub async fn wait(self) -> JfResult<BTreeSet<PathBuf>> {
         tokio::task::spawn_blocking(move || {
-            const INTERVAL_MILLIS: u64 = 100;
+            const INTERVAL: Duration = Duration::from_millis(100);
+            let mut deadline: Option<Instant> = None;
+            let mut changed = BTreeSet::new();
             loop {
                 if self.canceller.is_canceled() {
                     break;
                 }
-                match self
-                    .rx
-                    .recv_timeout(std::time::Duration::from_millis(INTERVAL_MILLIS))
-                {
-                    Ok(event) => match event?.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => break,
-                        _ => {}
-                    },
+                let now = Instant::now();
+                let timeout = match deadline {
+                    Some(deadline) if deadline <= now => break,
+                    Some(deadline) => (deadline - now).min(INTERVAL),
+                    None => INTERVAL,
+                };
+                match self.rx.recv_timeout(timeout) {
+                    Ok(event) => {
+                        let event = event?;
+                        let is_change = matches!(
+                            event.kind,
+                            EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+                        );
+                        let mut targets = event
+                            .paths
+                            .into_iter()
+                            .filter(|p| self.filter.is_target(p))
+                            .peekable();
+                        if is_change && targets.peek().is_some() {
+                            changed.extend(targets);
+                            deadline = Some(Instant::now() + self.debounce);
+                        }
+                    }
                     Err(e) => match e {
                         std::sync::mpsc::RecvTimeoutError::Timeout => continue,
                         std::sync::mpsc::RecvTimeoutError::Disconnected => {
@@ -58,7 +99,7 @@ impl JfWatcher {
                     },
                 }
             }
-            Ok(())
+            Ok(changed)
         })
         .await?
     }
@@ -24,6 +24,8 @@ where
     async fn start(&self) -> JfResult<Self>;
     async fn cancel(&self) -> JfResult<Self>;
     async fn join(&self) -> JfResult<JoinStatus>;
+    /// send `signal` to the running processes without cancelling
+    async fn signal(&self, signal: i32) -> JfResult<()>;
 
     fn set_canceller(&mut self, _: Canceller) -> Self;
 
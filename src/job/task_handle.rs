@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use super::{finish_notify::FinishNotify, join_status::JoinStatus, runner::JfHandle};
+use crate::util::error::{IntoJfError, JfResult};
+
+#[derive(Default)]
+enum State {
+    #[default]
+    NotStarted,
+    Running(JfHandle),
+    /// error is kept as message to return it to every caller
+    Finished(Result<JoinStatus, String>),
+}
+
+/// Handle of the task running a job, which can be joined any number of times
+///
+/// The first caller of `join` awaits the task and the others get the same result.
+#[derive(Clone, Default)]
+pub(super) struct TaskHandle {
+    state: Arc<Mutex<State>>,
+}
+
+impl TaskHandle {
+    pub async fn set(&self, handle: JfHandle) {
+        *self.state.lock().await = State::Running(handle);
+    }
+
+    /// result of the task, or `None` if not started yet
+    pub async fn join(&self) -> JfResult<Option<JoinStatus>> {
+        let mut state = self.state.lock().await;
+        if let State::Running(handle) = &mut *state {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(e) => Err(e.into()),
+            };
+            *state = State::Finished(result.as_ref().copied().map_err(ToString::to_string));
+            return result.map(Some);
+        }
+        match &*state {
+            State::Finished(Ok(status)) => Ok(Some(*status)),
+            State::Finished(Err(message)) => Err(message.clone().into_jf_error()),
+            _ => Ok(None),
+        }
+    }
+
+    /// result of the task once it notified `finish_notify`
+    ///
+    /// Blocks until the job is started and finished. The task may notify before `start` has
+    /// stored its handle, so this yields until the handle is set.
+    pub async fn join_finished(&self, finish_notify: &FinishNotify) -> JfResult<JoinStatus> {
+        finish_notify.wait().await;
+        loop {
+            if let Some(status) = self.join().await? {
+                return Ok(status);
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{job::join_status::Failure, util::testutil::*};
+
+    #[test]
+    #[coverage(off)]
+    fn join_twice() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let handle = TaskHandle::default();
+                assert_eq!(handle.join().await?, None);
+                handle
+                    .set(tokio::spawn(async {
+                        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                        Ok(JoinStatus::Failed(Failure::Code(3)))
+                    }))
+                    .await;
+                let other = handle.clone();
+                let (first, second) = tokio::join!(handle.join(), other.join());
+                assert_eq!(first?, Some(JoinStatus::Failed(Failure::Code(3))));
+                assert_eq!(second?, Some(JoinStatus::Failed(Failure::Code(3))));
+                assert_eq!(
+                    handle.join().await?,
+                    Some(JoinStatus::Failed(Failure::Code(3)))
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn join_error_twice() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let handle = TaskHandle::default();
+                handle
+                    .set(tokio::spawn(async { Err("broken".into_jf_error()) }))
+                    .await;
+                assert!(handle.join().await.is_err());
+                assert!(handle.join().await.is_err());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn join_finished_before_set() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async {
+                let handle = TaskHandle::default();
+                let finish_notify = FinishNotify::new();
+                finish_notify.notify(); // the task finished before its handle is set
+                let (status, _) = tokio::join!(handle.join_finished(&finish_notify), async {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    handle
+                        .set(tokio::spawn(async {
+                            Ok(JoinStatus::Failed(Failure::Code(3)))
+                        }))
+                        .await;
+                });
+                assert_eq!(status?, JoinStatus::Failed(Failure::Code(3)));
+                Ok(())
+            },
+        )
+    }
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use crate::util::error::{IntoJfError, JfResult};
+
+/// Job name of `name(key=value, ...)`
+pub fn name(call: &str) -> &str {
+    call.split_once('(').map_or(call, |(name, _)| name).trim()
+}
+
+/// Parse `name(key=value, ...)` into job name and params
+///
+/// `name` without parentheses has no params.
+pub fn parse(call: &str) -> JfResult<(String, HashMap<String, String>)> {
+    let Some((name, rest)) = call.split_once('(') else {
+        return Ok((call.trim().to_owned(), HashMap::new()));
+    };
+    let inner = rest
+        .trim_end()
+        .strip_suffix(')')
+        .ok_or(format!("Invalid job call `{call}` (missing `)`)").into_jf_error())?;
+    let params = inner
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or(format!("Invalid param `{pair}` in job call `{call}`").into_jf_error())?;
+            Ok((key.trim().to_owned(), value.trim().to_owned()))
+        })
+        .collect::<JfResult<_>>()?;
+    Ok((name.trim().to_owned(), params))
+}
+
+/// Normalized call of `name` with `params` to identify the same job with the same params
+pub fn canonical(name: &str, params: HashMap<String, String>) -> String {
+    if params.is_empty() {
+        return name.to_owned();
+    }
+    let mut params = params
+        .into_iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>();
+    params.sort();
+    format!("{name}({})", params.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn parse_call() -> JfResult<()> {
+        assert_eq!(parse("build")?, ("build".to_owned(), HashMap::new()));
+        assert_eq!(parse("build()")?, ("build".to_owned(), HashMap::new()));
+        assert_eq!(
+            parse("build(target=release, features = a b)")?,
+            (
+                "build".to_owned(),
+                HashMap::from([
+                    ("target".to_owned(), "release".to_owned()),
+                    ("features".to_owned(), "a b".to_owned()),
+                ])
+            )
+        );
+        assert_eq!(name("build(target=release)"), "build");
+        assert_eq!(name("build"), "build");
+        let (name, params) = parse(" build ")?;
+        assert_eq!(canonical(&name, params), "build");
+        let (name, params) = parse("build(b=2, a=1)")?;
+        assert_eq!(canonical(&name, params), "build(a=1,b=2)");
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn parse_invalid() {
+        assert!(parse("build(target=release").is_err());
+        assert!(parse("build(target)").is_err());
+    }
+}
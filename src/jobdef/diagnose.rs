@@ -0,0 +1,361 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use super::{call, Jobdef, JobdefPool};
+use crate::{cfg::source::Severity, ctx::Ctx, util::suggest::did_you_mean};
+
+/// Problem of a job found by `jf --validate`
+#[cfg_attr(test, derive(Debug))]
+pub struct Problem {
+    pub severity: Severity,
+    pub job_name: String,
+    /// key of the job causing the problem
+    pub key: Option<&'static str>,
+    /// value in `key` causing the problem (e.g. a job in `jobs`)
+    pub item: Option<String>,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(job_name: &str, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            job_name: job_name.to_owned(),
+            key: None,
+            item: None,
+            message,
+        }
+    }
+
+    fn warning(job_name: &str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(job_name, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.severity, Severity::Error)
+    }
+
+    fn at(mut self, key: &'static str, item: Option<&str>) -> Self {
+        self.key = Some(key);
+        self.item = item.map(str::to_owned);
+        self
+    }
+}
+
+/// value decided by params, so it can be checked only when built
+fn is_template(value: &str) -> bool {
+    value.contains("{{")
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// search `command` in `path_var` like shells (relative to `cwd` if it contains `/`)
+fn command_exists(command: &str, cwd: &Path, path_var: Option<OsString>) -> bool {
+    if command.contains('/') {
+        return is_executable(&cwd.join(command));
+    }
+    path_var
+        .map(|path_var| {
+            std::env::split_paths(&path_var).any(|dir| is_executable(&dir.join(command)))
+        })
+        .unwrap_or(false)
+}
+
+impl JobdefPool {
+    /// problems of every job in order of job name
+    ///
+    /// Building jobs (e.g. params, cycles) is checked only if the definitions have no error,
+    /// since a broken job also breaks every job referencing it.
+    pub fn diagnose(&self, ctx: Ctx) -> Vec<Problem> {
+        let mut jobdefs = self.map.values().collect::<Vec<_>>();
+        jobdefs.sort_by_key(|jobdef| jobdef.name());
+
+        let referenced = jobdefs
+            .iter()
+            .flat_map(|jobdef| self.references(&ctx, jobdef))
+            .map(|(_, _, resolved)| resolved)
+            .collect::<HashSet<_>>();
+        let mut problems = jobdefs
+            .iter()
+            .flat_map(|jobdef| {
+                let mut problems = self.check_references(&ctx, jobdef);
+                problems.extend(check_jobs(jobdef));
+                problems.extend(check_visibility(jobdef, &referenced));
+                problems.extend(check_watch_list(&ctx, jobdef));
+                problems.extend(check_command(&ctx, jobdef));
+                problems
+            })
+            .collect::<Vec<_>>();
+        if problems.iter().any(Problem::is_error) {
+            return problems;
+        }
+
+        problems.extend(jobdefs.iter().filter_map(|jobdef| {
+            let e = jobdef.validate(ctx.clone(), self.clone()).err()?;
+            Some(Problem::new(jobdef.name(), e.to_string()))
+        }));
+        problems
+    }
+
+    /// (key, reference, resolved job name) of jobs referenced by `jobdef`
+    fn references<'a>(
+        &self,
+        ctx: &Ctx,
+        jobdef: &'a Jobdef,
+    ) -> Vec<(&'static str, &'a String, String)> {
+        let ctx = ctx.clone().push_job(jobdef.name());
+        jobdef
+            .job_cfg
+            .references()
+            .into_iter()
+            .filter(|(_, reference)| !is_template(reference))
+            .filter_map(|(key, reference)| {
+                let (job_name, _) = call::parse(reference).ok()?;
+                Some((key, reference, self.resolve(&ctx, job_name)))
+            })
+            .collect()
+    }
+
+    fn check_references(&self, ctx: &Ctx, jobdef: &Jobdef) -> Vec<Problem> {
+        self.references(ctx, jobdef)
+            .into_iter()
+            .filter(|(_, _, resolved)| !self.map.contains_key(resolved))
+            .map(|(key, reference, resolved)| {
+                let mut message =
+                    format!("job.{} references unknown job `{resolved}`", jobdef.name());
+                if let Some(suggestion) =
+                    did_you_mean(&resolved, self.map.keys().map(String::as_str))
+                {
+                    message.push_str(&format!("\nDid you mean `{suggestion}`?"));
+                }
+                Problem::new(jobdef.name(), message).at(key, Some(reference))
+            })
+            .collect()
+    }
+}
+
+fn check_jobs(jobdef: &Jobdef) -> Option<Problem> {
+    match jobdef.job_cfg.jobs() {
+        Some(jobs) if jobs.is_empty() => Some(
+            Problem::new(
+                jobdef.name(),
+                format!("job.{} has empty `jobs`", jobdef.name()),
+            )
+            .at("jobs", None),
+        ),
+        _ => None,
+    }
+}
+
+/// private job which is not referenced never runs, though it is harmless
+fn check_visibility(jobdef: &Jobdef, referenced: &HashSet<String>) -> Option<Problem> {
+    if jobdef.is_public() || referenced.contains(jobdef.name()) {
+        return None;
+    }
+    let message = format!(
+        "job.{} is private but no job references it\n\
+         Please remove `visibility = \"private\"` to run it",
+        jobdef.name()
+    );
+    Some(Problem::warning(jobdef.name(), message).at("visibility", None))
+}
+
+fn base_dir(ctx: &Ctx) -> PathBuf {
+    ctx.cwd()
+        .cloned()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+fn check_watch_list(ctx: &Ctx, jobdef: &Jobdef) -> Vec<Problem> {
+    let base = jobdef
+        .job_cfg
+        .base()
+        .cloned()
+        .unwrap_or_else(|| base_dir(ctx));
+    jobdef
+        .watch_list()
+        .iter()
+        .filter(|pattern| !is_template(pattern))
+        .filter(|pattern| {
+            glob::glob(&base.join(pattern).to_string_lossy())
+                .map(|mut paths| paths.next().is_none())
+                .unwrap_or(true)
+        })
+        .map(|pattern| {
+            Problem::new(
+                jobdef.name(),
+                format!(
+                    "job.{}: `{pattern}` in `watch_list` matches no file",
+                    jobdef.name()
+                ),
+            )
+            .at("watch_list", Some(pattern))
+        })
+        .collect()
+}
+
+fn check_command(ctx: &Ctx, jobdef: &Jobdef) -> Option<Problem> {
+    let params = jobdef.job_cfg.command()?;
+    if is_template(&params.command) {
+        return None;
+    }
+    let cwd = params.cwd.clone().unwrap_or_else(|| base_dir(ctx));
+    let path_var = params
+        .env
+        .get("PATH")
+        .or(ctx.env().get("PATH"))
+        .or(jobdef.job_cfg.file_env().get("PATH"))
+        .map(OsString::from)
+        .or_else(|| std::env::var_os("PATH"));
+    if command_exists(&params.command, &cwd, path_var) {
+        return None;
+    }
+    Some(
+        Problem::new(
+            jobdef.name(),
+            format!(
+                "job.{}: command `{}` not found on PATH",
+                jobdef.name(),
+                params.command
+            ),
+        )
+        .at("command", None),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{error::JfResult, testutil::*};
+
+    #[coverage(off)]
+    fn jobdef(name: &str, cfg: &str) -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    }
+
+    #[coverage(off)]
+    fn messages(problems: &[Problem]) -> Vec<String> {
+        problems.iter().map(|p| p.message.clone()).collect()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn command_search() {
+        let cwd = std::env::current_dir().unwrap();
+        assert!(command_exists("sh", &cwd, std::env::var_os("PATH")));
+        assert!(!command_exists(
+            "jf-unknown-command",
+            &cwd,
+            std::env::var_os("PATH")
+        ));
+        assert!(!command_exists("sh", &cwd, None));
+        assert!(command_exists("/bin/sh", &cwd, None));
+        assert!(!command_exists("./Cargo.toml", &cwd, None)); // not executable
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn valid() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef("build", "command = \"echo\"\nwatch_list = [\"src/**\"]")?,
+                    jobdef(
+                        "test",
+                        "command = \"{{cmd}}\"\nparams = { cmd = { default = \"echo\" } }",
+                    )?,
+                    jobdef(
+                        "ci",
+                        "mode = \"sequential\"\njobs = [\"build\", \"lint\", \"test(cmd=true)\"]",
+                    )?,
+                    jobdef("lint", "command = \"echo\"\nvisibility = \"private\"")?,
+                ]);
+                assert!(pool.diagnose(Ctx::async_fixture().await).is_empty());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn problems() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef("build", "command = \"carg\"")?,
+                    jobdef("ci", "mode = \"parallel\"\njobs = [\"build\", \"tset\"]")?,
+                    jobdef("empty", "mode = \"sequential\"\njobs = []")?,
+                    jobdef("hidden", "command = \"echo\"\nvisibility = \"private\"")?,
+                    jobdef("test", "command = \"echo\"\nwatch_list = [\"unknown/**\"]")?,
+                ]);
+                let problems = pool.diagnose(Ctx::async_fixture().await);
+                assert_eq!(
+                    messages(&problems),
+                    vec![
+                        "job.build: command `carg` not found on PATH",
+                        "job.ci references unknown job `tset`\nDid you mean `test`?",
+                        "job.empty has empty `jobs`",
+                        "job.hidden is private but no job references it\nPlease remove `visibility = \"private\"` to run it",
+                        "job.test: `unknown/**` in `watch_list` matches no file",
+                    ]
+                );
+                assert_eq!(problems[1].key, Some("jobs"));
+                assert_eq!(problems[1].item.as_deref(), Some("tset"));
+                assert!(!problems[3].is_error());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn build_problems() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef("a", "mode = \"sequential\"\njobs = [\"b\"]")?,
+                    jobdef("b", "mode = \"sequential\"\njobs = [\"a\"]")?,
+                ]);
+                let problems = pool.diagnose(Ctx::async_fixture().await);
+                assert_eq!(
+                    messages(&problems),
+                    vec![
+                        "Job reference cycle: b -> a -> b", // found while building `b` in `a`
+                        "Job reference cycle: a -> b -> a",
+                    ]
+                );
+                assert_eq!(problems[0].job_name, "a");
+
+                // warnings do not skip building jobs
+                let pool = JobdefPool::new(vec![
+                    jobdef("a", "mode = \"sequential\"\njobs = [\"a\"]")?,
+                    jobdef("hidden", "command = \"echo\"\nvisibility = \"private\"")?,
+                ]);
+                let problems = pool.diagnose(Ctx::async_fixture().await);
+                assert_eq!(
+                    messages(&problems),
+                    vec![
+                        "job.hidden is private but no job references it\nPlease remove `visibility = \"private\"` to run it",
+                        "Job reference cycle: a -> a",
+                    ]
+                );
+                Ok(())
+            },
+        )
+    }
+}
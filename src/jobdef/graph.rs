@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::{HashSet, VecDeque};
+
+use super::{call, JobdefPool};
+use crate::{
+    cfg::job_cfg::Visibility,
+    ctx::Ctx,
+    util::error::{IntoJfError, JfResult},
+};
+
+/// Job in `jf --graph`
+pub struct Node {
+    pub name: String,
+    /// `None` if the name is given by params (e.g. `{{job}}`)
+    pub mode: Option<&'static str>,
+    pub visibility: Visibility,
+}
+
+/// Reference from a job to another
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    /// order of `jobs` in `sequential`, or the key except `jobs` (e.g. `finally`)
+    pub label: Option<String>,
+}
+
+/// Jobs reachable from a job by references
+pub struct Graph {
+    /// in order of discovery from the root job
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl JobdefPool {
+    /// jobs referenced by `job_name` directly or indirectly
+    pub fn graph(&self, ctx: &Ctx, job_name: String) -> JfResult<Graph> {
+        let root = self.get(job_name)?.name().clone();
+        let mut graph = Graph {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut queue = VecDeque::from([root.clone()]);
+        let mut visited = HashSet::from([root]);
+        while let Some(name) = queue.pop_front() {
+            let Some(jobdef) = self.map.get(&name) else {
+                graph.nodes.push(Node {
+                    name,
+                    mode: None,
+                    visibility: Visibility::Public,
+                });
+                continue;
+            };
+            let ctx = ctx.clone().push_job(&name);
+            let sequential = jobdef.job_cfg.mode() == "sequential";
+            let mut step = 0;
+            for (key, reference) in jobdef.job_cfg.references() {
+                let to = match call::parse(reference) {
+                    Ok((job_name, _)) if !reference.contains("{{") => {
+                        let job_name = self.resolve(&ctx, job_name);
+                        if !self.map.contains_key(&job_name) {
+                            return Err(format!("job.{name} references unknown job `{job_name}`")
+                                .into_jf_error());
+                        }
+                        job_name
+                    }
+                    _ => reference.clone(),
+                };
+                let label = match key {
+                    "jobs" if sequential => {
+                        step += 1;
+                        Some(step.to_string())
+                    }
+                    "jobs" | "job" => None,
+                    key => Some(key.to_owned()),
+                };
+                if visited.insert(to.clone()) {
+                    queue.push_back(to.clone());
+                }
+                graph.edges.push(Edge {
+                    from: name.clone(),
+                    to,
+                    label,
+                });
+            }
+            graph.nodes.push(Node {
+                name,
+                mode: Some(jobdef.job_cfg.mode()),
+                visibility: jobdef.visibility.clone(),
+            });
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{jobdef::Jobdef, util::testutil::*};
+
+    #[coverage(off)]
+    fn jobdef(name: &str, cfg: &str) -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    }
+
+    #[coverage(off)]
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.label.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn graph() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef(
+                        "ci",
+                        "mode = \"sequential\"\njobs = [\"checks\", \"build(x=1)\"]\nfinally = [\"clean\"]",
+                    )?,
+                    jobdef("checks", "mode = \"parallel\"\njobs = [\"lint\", \"build\"]")?,
+                    jobdef("build", "command = \"cargo\"\ndepends_on = [\"lint\"]")?,
+                    jobdef("lint", "command = \"cargo\"\nvisibility = \"private\"")?,
+                    jobdef("clean", "command = \"cargo\"")?,
+                    jobdef("other", "command = \"cargo\"")?,
+                ]);
+                let graph = pool.graph(&Ctx::async_fixture().await, "ci".into())?;
+                let nodes = graph
+                    .nodes
+                    .iter()
+                    .map(|n| (n.name.as_str(), n.mode.unwrap(), n.visibility.is_public()))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    nodes,
+                    vec![
+                        ("ci", "sequential", true),
+                        ("checks", "parallel", true),
+                        ("build", "command", true),
+                        ("clean", "command", true),
+                        ("lint", "command", false),
+                    ]
+                );
+                assert_eq!(
+                    edges(&graph),
+                    vec![
+                        ("ci", "checks", Some("1")),
+                        ("ci", "build", Some("2")),
+                        ("ci", "clean", Some("finally")),
+                        ("checks", "lint", None),
+                        ("checks", "build", None),
+                        ("build", "lint", Some("depends_on")),
+                    ]
+                );
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn graph_cycle_and_template() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef("a", "mode = \"sequential\"\njobs = [\"b\", \"{{next}}\"]")?,
+                    jobdef(
+                        "b",
+                        "mode = \"watch\"\njob = \"a\"\nwatch_list = [\"src/**\"]",
+                    )?,
+                ]);
+                let graph = pool.graph(&Ctx::async_fixture().await, "a".into())?;
+                assert_eq!(
+                    edges(&graph),
+                    vec![
+                        ("a", "b", Some("1")),
+                        ("a", "{{next}}", Some("2")),
+                        ("b", "a", None)
+                    ]
+                );
+                assert_eq!(graph.nodes.len(), 3);
+                assert!(graph.nodes[2].mode.is_none());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn graph_unknown() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool =
+                    JobdefPool::new(vec![jobdef("a", "mode = \"parallel\"\njobs = [\"b\"]")?]);
+                let ctx = Ctx::async_fixture().await;
+                assert!(pool.graph(&ctx, "a".into()).is_err());
+                assert!(pool.graph(&ctx, "unknown".into()).is_err());
+                Ok(())
+            },
+        )
+    }
+}
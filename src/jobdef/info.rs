@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::{call, JobdefPool};
+use crate::{cfg::job_cfg::Visibility, ctx::Ctx};
+
+/// Summary of a job for `jf --list --format`
+#[derive(Serialize)]
+pub struct JobInfo {
+    pub name: String,
+    pub mode: &'static str,
+    pub description: String,
+    pub visibility: Visibility,
+    /// referenced jobs (`jobs`, `finally`, `job` and `depends_on`) without params
+    pub jobs: Vec<String>,
+    pub watch_list: Vec<String>,
+    /// cfg file defining the job
+    pub source: Option<PathBuf>,
+}
+
+impl JobdefPool {
+    /// summary of public jobs (and private ones if `all`) in order of name
+    pub fn infos(&self, ctx: &Ctx, all: bool) -> Vec<JobInfo> {
+        let mut infos = self
+            .map
+            .values()
+            .filter(|jobdef| all || jobdef.is_public())
+            .map(|jobdef| {
+                let ctx = ctx.clone().push_job(jobdef.name());
+                let mut jobs = Vec::new();
+                for (_, reference) in jobdef.job_cfg.references() {
+                    let job_name = match call::parse(reference) {
+                        Ok((job_name, _)) => self.resolve(&ctx, job_name),
+                        Err(_) => reference.clone(), // e.g. `{{job}}`
+                    };
+                    if !jobs.contains(&job_name) {
+                        jobs.push(job_name);
+                    }
+                }
+                JobInfo {
+                    name: jobdef.name().clone(),
+                    mode: jobdef.job_cfg.mode(),
+                    description: jobdef.description().clone(),
+                    visibility: jobdef.visibility.clone(),
+                    jobs,
+                    watch_list: jobdef.watch_list().clone(),
+                    source: None,
+                }
+            })
+            .collect::<Vec<_>>();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        jobdef::Jobdef,
+        util::{error::JfResult, testutil::*},
+    };
+
+    #[coverage(off)]
+    fn jobdef(name: &str, cfg: &str) -> JfResult<Jobdef> {
+        Jobdef::new(name.into(), toml::from_str(cfg)?)
+    }
+
+    /// job of cfg file included as `api`
+    #[coverage(off)]
+    fn included(mut jobdef: Jobdef) -> Jobdef {
+        jobdef.job_cfg.include("api", &Default::default());
+        jobdef
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn infos() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    jobdef("build", "command = \"cargo\"\nvisibility = \"private\"")?,
+                    included(jobdef(
+                        "api:test",
+                        "command = \"cargo\"\nwatch_list = [\"src/**\"]",
+                    )?),
+                    included(jobdef(
+                        "api:ci",
+                        "mode = \"sequential\"\njobs = [\"test\", \"build\"]\n\
+                         depends_on = [\"build(x=1)\"]\ndescription = \"ci of api\"",
+                    )?),
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                let infos = pool.infos(&ctx, false);
+                let names = infos.iter().map(|i| i.name.as_str()).collect::<Vec<_>>();
+                assert_eq!(names, vec!["api:ci", "api:test"]);
+                assert_eq!(infos[0].mode, "sequential");
+                assert_eq!(infos[0].description, "ci of api");
+                assert_eq!(infos[0].jobs, vec!["api:test", "build"]);
+                assert_eq!(infos[1].watch_list, vec!["src/**"]);
+                assert_eq!(pool.infos(&ctx, true).len(), 3);
+                Ok(())
+            },
+        )
+    }
+}
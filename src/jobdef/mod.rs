@@ -1,16 +1,29 @@
 // SPDX-License-Identifier: MPL-2.0
 mod agent;
+pub mod call;
+mod diagnose;
+pub mod graph;
+mod info;
 mod pool;
 
+use std::collections::HashMap;
+
 pub use self::agent::Agent;
+pub use self::info::JobInfo;
 pub use self::pool::JobdefPool;
 use crate::{
     cfg::job_cfg::{JobCfg, Visibility},
     ctx::Ctx,
     job::Job,
-    util::error::{IntoJfError, JfError, JfResult},
+    util::{
+        error::{IntoJfError, JfError, JfResult},
+        template,
+    },
 };
 
+/// template variable of files changed before `watch` restarted the job (quoted, space separated)
+pub const CHANGED_FILES_VAR: &str = "changed_files";
+
 pub struct Jobdef {
     name: String,
     visibility: Visibility,
@@ -28,6 +41,11 @@ impl Jobdef {
         })
     }
 
+    /// namespace given by `include` (`None` for jobs of the root cfg file)
+    fn namespace(&self) -> Option<&String> {
+        self.job_cfg.namespace()
+    }
+
     fn is_public(&self) -> bool {
         self.visibility.is_public()
     }
@@ -47,18 +65,84 @@ impl Jobdef {
         }
     }
 
+    /// resolve values of params from given ones (`ctx.params()`) and defaults
+    fn resolve_params(&self, given: &HashMap<String, String>) -> JfResult<HashMap<String, String>> {
+        let defs = self.job_cfg.params();
+        if let Some(unknown) = given.keys().find(|name| !defs.contains_key(*name)) {
+            return Err(
+                format!("job.{} does not have param `{unknown}`", self.name).into_jf_error()
+            );
+        }
+        defs.iter()
+            .map(|(name, def)| {
+                let value = given.get(name).or(def.default()).ok_or(
+                    format!("param `{name}` of job.{} is required", self.name).into_jf_error(),
+                )?;
+                def.check(name, value)
+                    .map_err(|e| format!("job.{}: {e}", self.name).into_jf_error())?;
+                Ok((name.clone(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// build with placeholder values for params which are required
+    fn validate(&self, ctx: Ctx, pool: JobdefPool) -> JfResult<Job> {
+        let placeholders = self
+            .job_cfg
+            .params()
+            .iter()
+            .filter(|(_, def)| def.default().is_none())
+            .map(|(name, def)| (name.clone(), def.placeholder()))
+            .collect();
+        self.build(ctx.with_params(placeholders), pool, Agent::Job)
+    }
+
     fn build(&self, ctx: Ctx, pool: JobdefPool, agent: Agent) -> JfResult<Job> {
-        let ctx = ctx.new_span(&self.name);
+        let ctx = ctx
+            .new_span(&self.name)
+            .with_file_env(self.job_cfg.file_env().clone());
         self.visibility_guard(agent)?;
+        let mut params = self.resolve_params(ctx.params())?;
+        params
+            .entry(CHANGED_FILES_VAR.to_owned())
+            .or_insert_with(|| {
+                let quoted = ctx.changed_files().iter().map(|f| template::quote(f));
+                quoted.collect::<Vec<_>>().join(" ")
+            });
+        let ctx = ctx.with_params(params);
         ctx.engine_log("creating job runner");
         ctx.scopeout_engine_log("created job runner");
-        Job::new(ctx, &self.job_cfg, pool)
+        let deps = self
+            .job_cfg
+            .depends_on()
+            .iter()
+            .map(|dep| {
+                let dep = template::render(dep, ctx.params())?;
+                pool.build_shared(ctx.without_args().without_params(), &dep)
+            })
+            .collect::<JfResult<Vec<_>>>()?;
+        let mut job = Job::new(ctx.clone(), &self.job_cfg, pool)?;
+        if let Some(timeout) = self.job_cfg.timeout() {
+            job = job.timeout(ctx.clone(), timeout); // per attempt of `retry`
+        }
+        if let Some(retry) = self.job_cfg.retry() {
+            job = job.retry(ctx.clone(), retry.clone());
+        }
+        if deps.is_empty() {
+            Ok(job)
+        } else {
+            Ok(job.depends_on(ctx, deps))
+        }
     }
 
     fn name(&self) -> &String {
         &self.name
     }
 
+    fn watch_list(&self) -> &Vec<String> {
+        self.job_cfg.watch_list()
+    }
+
     pub fn description(&self) -> &String {
         &self.description
     }
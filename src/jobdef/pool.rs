@@ -1,22 +1,35 @@
 // SPDX-License-Identifier: MPL-2.0
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
-use super::{Agent, Jobdef};
+use super::{call, Agent, Jobdef};
 use crate::{
     ctx::Ctx,
-    job::Job,
-    util::error::{IntoJfError, JfError, JfResult},
+    job::{modes::WatchParams, Job, SharedJob},
+    util::error::{IntoJfError, JfResult},
 };
 
 #[derive(Clone)]
 pub struct JobdefPool {
-    map: Arc<HashMap<String, Jobdef>>,
+    pub(super) map: Arc<HashMap<String, Jobdef>>,
+    /// dependencies (`depends_on`) shared by every dependent in a run (see [`Self::fresh`])
+    shared: Arc<Mutex<HashMap<String, SharedJob>>>,
 }
 
 impl JobdefPool {
     pub fn new(jobdefs: Vec<Jobdef>) -> Self {
         let map = HashMap::from_iter(jobdefs.into_iter().map(|jd| (jd.name().to_owned(), jd)));
-        Self { map: Arc::new(map) }
+        Self {
+            map: Arc::new(map),
+            shared: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn list_all(&self) -> Vec<String> {
+        self.map.keys().cloned().collect()
     }
 
     pub fn list_public(&self) -> Vec<String> {
@@ -32,30 +45,125 @@ impl JobdefPool {
             .collect()
     }
 
-    pub fn validate(&self, ctx: Ctx) -> JfResult<()> {
-        let errs = self
-            .map
-            .values()
-            .map(|jobdef| jobdef.build(ctx.clone(), self.clone(), Agent::Job))
-            .filter_map(|res| res.err())
-            .collect::<Vec<_>>();
-        if errs.is_empty() {
-            Ok(())
-        } else {
-            Err(JfError::Multi(errs))
+    /// pool for a new run, where dependencies (`depends_on`) run again
+    pub fn fresh(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            shared: Default::default(),
         }
     }
 
-    fn get(&self, job_name: String) -> JfResult<&Jobdef> {
+    /// working directory of the job called as `job_call` from `ctx` (`None` if not decided)
+    pub fn cwd(&self, ctx: &Ctx, job_call: &str) -> Option<PathBuf> {
+        let (job_name, _) = call::parse(job_call).ok()?;
+        let jobdef = self.map.get(&self.resolve(ctx, job_name))?;
+        jobdef.job_cfg.cwd().or(ctx.cwd()).cloned()
+    }
+
+    /// namespace of the job given by `include` (`None` for jobs of the root cfg file)
+    pub fn namespace(&self, job_name: &str) -> Option<&str> {
+        self.map.get(job_name)?.namespace().map(String::as_str)
+    }
+
+    pub(super) fn get(&self, job_name: String) -> JfResult<&Jobdef> {
         self.map
             .get(&job_name)
             .ok_or(format!("Jobdef(name={job_name}) not found").into_jf_error())
     }
 
+    /// build job from `job_name` or `job_name(key=value, ...)`
+    ///
+    /// params in the call are added to the ones in `ctx` (e.g. given from cli)
     pub fn build(&self, ctx: Ctx, job_name: String, agent: Agent) -> JfResult<Job> {
+        let (job_name, call_params) = super::call::parse(&job_name)?;
+        let job_name = self.resolve(&ctx, job_name);
+        Self::cycle_guard(&ctx, &job_name)?;
+        let mut params = ctx.params().clone();
+        params.extend(call_params);
+        let ctx = ctx.with_params(params).push_job(&job_name);
         self.get(job_name)?.build(ctx, self.clone(), agent)
     }
 
+    /// build job wrapped by `watch` (`watch_list` of the job if `watch_list` is empty)
+    pub fn build_watch(
+        &self,
+        ctx: Ctx,
+        job_name: String,
+        watch_list: Vec<String>,
+        agent: Agent,
+    ) -> JfResult<Job> {
+        let jobdef = self.get(job_name.clone())?;
+        jobdef.visibility_guard(agent)?;
+        let watch_list = match watch_list.is_empty() {
+            true => jobdef.watch_list().clone(),
+            false => watch_list,
+        };
+        if watch_list.is_empty() {
+            return Err(format!(
+                "job.{job_name} has no `watch_list`\nPlease add `watch_list` to the job or use `--watch=<GLOB>`"
+            )
+            .into_jf_error());
+        }
+        let params = WatchParams {
+            base: jobdef.job_cfg.base().cloned(),
+            ..WatchParams::new(job_name, watch_list)
+        };
+        Job::watch(ctx, params, self.clone())
+    }
+
+    /// name of the job referenced as `job_name` from the current job of `ctx`
+    ///
+    /// Jobs of included cfg (e.g. `api:ci`) refer to jobs in the same namespace (`api:test`)
+    /// by their short name (`test`), then to the ones in outer namespaces.
+    /// `:` in names of jobs in the root cfg (e.g. `test:live`) is not a namespace.
+    pub(super) fn resolve(&self, ctx: &Ctx, job_name: String) -> String {
+        let mut namespace = ctx
+            .job_path()
+            .last()
+            .and_then(|current| self.namespace(current));
+        while let Some(ns) = namespace {
+            let name = format!("{ns}:{job_name}");
+            if self.map.contains_key(&name) {
+                return name;
+            }
+            namespace = ns.rsplit_once(':').map(|(outer, _)| outer);
+        }
+        job_name
+    }
+
+    /// detect job which references itself directly or indirectly
+    fn cycle_guard(ctx: &Ctx, job_name: &str) -> JfResult<()> {
+        let path = ctx.job_path();
+        match path.iter().position(|name| name == job_name) {
+            Some(index) => {
+                let cycle = path[index..]
+                    .iter()
+                    .map(String::as_str)
+                    .chain([job_name])
+                    .collect::<Vec<_>>();
+                Err(format!("Job reference cycle: {}", cycle.join(" -> ")).into_jf_error())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// build dependency which runs at most once in this run
+    pub fn build_shared(&self, ctx: Ctx, job_call: &str) -> JfResult<SharedJob> {
+        let (job_name, params) = call::parse(job_call)?;
+        let key = call::canonical(&self.resolve(&ctx, job_name), params);
+        if let Some(job) = self.shared.lock().unwrap().get(&key) {
+            return Ok(job.clone());
+        }
+        let job = SharedJob::new(self.build(ctx, key.clone(), Agent::Job)?);
+        Ok(self
+            .shared
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert(job)
+            .clone())
+    }
+
     pub fn description(&self, job_name: String) -> JfResult<&String> {
         Ok(self.get(job_name)?.description())
     }
@@ -66,6 +174,7 @@ mod tests {
     use super::*;
     use crate::{
         cfg::job_cfg::{CommonCfg, JobCfg, MockCfg, Visibility, WatchCfg},
+        jobdef::diagnose::Problem,
         logging::LoggingMock,
         util::testutil::*,
     };
@@ -108,9 +217,10 @@ mod tests {
                     )?,
                 ]);
                 assert_eq!(pool.list_public().len(), 2);
+                assert_eq!(pool.list_all().len(), 3);
                 let logging_mock = LoggingMock::new().await;
                 let ctx = Ctx::new(logging_mock.logger, "test", true);
-                assert!(pool.validate(ctx.clone()).is_ok());
+                assert!(!pool.diagnose(ctx.clone()).iter().any(Problem::is_error));
                 assert!(pool.build(ctx.clone(), "job1".into(), Agent::Job).is_ok());
                 assert!(pool.build(ctx.clone(), "job1".into(), Agent::Cli).is_ok());
                 assert!(pool.build(ctx.clone(), "job3".into(), Agent::Job).is_ok());
@@ -141,7 +251,7 @@ mod tests {
                 assert_eq!(pool.list_public().len(), 2);
                 let logging_mock = LoggingMock::new().await;
                 let ctx = Ctx::new(logging_mock.logger, "test", true);
-                assert!(pool.validate(ctx.clone()).is_err());
+                assert!(!pool.diagnose(ctx.clone()).is_empty());
                 assert!(pool.build(ctx.clone(), "job1".into(), Agent::Job).is_ok());
                 assert!(pool.build(ctx.clone(), "job1".into(), Agent::Cli).is_ok());
                 assert!(pool.build(ctx.clone(), "job3".into(), Agent::Job).is_err());
@@ -152,4 +262,175 @@ mod tests {
             },
         )
     }
+
+    #[coverage(off)]
+    fn mock_depends_on(name: &str, depends_on: &[&str]) -> JfResult<Jobdef> {
+        let cfg = format!(
+            "mode = \"mock\"\neach_sleep_time = 1\nsleep_count = 1\ndepends_on = {depends_on:?}"
+        );
+        Jobdef::new(name.into(), toml::from_str(&cfg)?)
+    }
+
+    /// job `name` of cfg file included with `namespace`
+    #[coverage(off)]
+    fn mock_included(namespace: &str, name: &str, depends_on: &[&str]) -> JfResult<Jobdef> {
+        let mut jobdef = mock_depends_on(&format!("{namespace}:{name}"), depends_on)?;
+        jobdef.job_cfg.include(namespace, &HashMap::new());
+        Ok(jobdef)
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn depends_on() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    mock_depends_on("build", &[])?,
+                    mock_depends_on("lint", &["build"])?,
+                    mock_depends_on("test", &["build"])?,
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                assert!(pool.diagnose(ctx.clone()).is_empty());
+                let lint = pool.build_shared(ctx.clone(), "lint")?;
+                let test = pool.build_shared(ctx.clone(), "test")?;
+                pool.build_shared(ctx, " build ")?;
+                assert_eq!(pool.shared.lock().unwrap().len(), 3); // build, lint, test
+                lint.start().await?;
+                test.start().await?;
+                assert!(lint.join().await?.is_succeed());
+                assert!(test.join().await?.is_succeed());
+                let fresh = pool.fresh(); // next run
+                assert!(fresh.shared.lock().unwrap().is_empty());
+                assert_eq!(fresh.list_all().len(), 3);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn resolve() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    mock_depends_on("test", &[])?,
+                    mock_depends_on("unit", &[])?,
+                    mock_depends_on("test:unit", &[])?,
+                    mock_depends_on("test:live", &["unit"])?,
+                    mock_included("api", "test", &[])?,
+                    mock_included("api", "ci", &["test"])?,
+                    mock_included("web", "ci", &["test", "api:test"])?,
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                let api = ctx.clone().push_job("api:ci");
+                let web = ctx.clone().push_job("web:ci");
+                let live = ctx.clone().push_job("test:live");
+                assert_eq!(pool.resolve(&ctx, "test".into()), "test");
+                assert_eq!(pool.resolve(&api, "test".into()), "api:test");
+                assert_eq!(pool.resolve(&web, "test".into()), "test");
+                assert_eq!(pool.resolve(&web, "api:test".into()), "api:test");
+                assert_eq!(pool.resolve(&live, "unit".into()), "unit"); // not a namespace
+                assert_eq!(pool.namespace("api:ci"), Some("api"));
+                assert_eq!(pool.namespace("test:live"), None);
+                assert!(pool.diagnose(ctx.clone()).is_empty());
+                pool.build_shared(api, "test")?;
+                pool.build_shared(web.clone(), "api:test")?;
+                pool.build_shared(web, "test")?;
+                assert_eq!(pool.shared.lock().unwrap().len(), 3); // api:test, test, unit
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn build_watch() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+                    Jobdef::new(name.into(), toml::from_str(cfg)?)
+                };
+                let mock = "mode = \"mock\"\neach_sleep_time = 1\nsleep_count = 1";
+                let pool = JobdefPool::new(vec![
+                    jobdef("test", &format!("{mock}\nwatch_list = [\"src/**\"]"))?,
+                    jobdef("run", mock)?,
+                    jobdef("hidden", &format!("{mock}\nvisibility = \"private\""))?,
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                assert!(matches!(
+                    pool.build_watch(ctx.clone(), "test".into(), vec![], Agent::Cli)?,
+                    Job::Watch(_)
+                ));
+                let watch_list = vec!["src/**".to_string()];
+                assert!(pool
+                    .build_watch(ctx.clone(), "run".into(), watch_list.clone(), Agent::Cli)
+                    .is_ok());
+                assert!(pool
+                    .build_watch(ctx.clone(), "run".into(), vec![], Agent::Cli)
+                    .is_err()); // no watch_list
+                assert!(pool
+                    .build_watch(ctx.clone(), "hidden".into(), watch_list.clone(), Agent::Cli)
+                    .is_err());
+                assert!(pool
+                    .build_watch(ctx, "unknown".into(), watch_list, Agent::Cli)
+                    .is_err());
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn depends_on_cycle() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let pool = JobdefPool::new(vec![
+                    mock_depends_on("a", &["b"])?,
+                    mock_depends_on("b", &["c"])?,
+                    mock_depends_on("c", &["a"])?,
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                assert!(!pool.diagnose(ctx.clone()).is_empty());
+                let err = pool.build_shared(ctx, "a").err().unwrap();
+                assert_eq!(err.to_string(), "Job reference cycle: a -> b -> c -> a");
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn reference_cycle() -> JfResult<()> {
+        async_test(
+            #[coverage(off)]
+            async move {
+                let jobdef = |name: &str, cfg: &str| -> JfResult<Jobdef> {
+                    Jobdef::new(name.into(), toml::from_str(cfg)?)
+                };
+                let pool = JobdefPool::new(vec![
+                    jobdef("a", "mode = \"sequential\"\njobs = [\"b\"]")?,
+                    jobdef("b", "mode = \"parallel\"\njobs = [\"c\"]")?,
+                    jobdef("c", "mode = \"watch\"\njob = \"a\"\nwatch_list = []")?,
+                    jobdef("self", "mode = \"sequential\"\njobs = [\"self\"]")?,
+                ]);
+                let ctx = Ctx::async_fixture().await;
+                let err = pool
+                    .build(ctx.clone(), "a".into(), Agent::Cli)
+                    .err()
+                    .unwrap();
+                assert_eq!(err.to_string(), "Job reference cycle: a -> b -> c -> a");
+                let err = pool
+                    .build(ctx.clone(), "self".into(), Agent::Cli)
+                    .err()
+                    .unwrap();
+                assert_eq!(err.to_string(), "Job reference cycle: self -> self");
+                assert_eq!(pool.diagnose(ctx).len(), 4);
+                Ok(())
+            },
+        )
+    }
 }
@@ -2,16 +2,24 @@
 pub mod log_generator;
 mod log_level;
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
 use crate::util::error::JfResult;
 
 pub use self::log_level::LogLevel;
 
+/// Message to the logging worker
+#[derive(Debug)]
+pub enum Message {
+    Line(String),
+    /// acknowledged after every line sent before it is written
+    Flush(oneshot::Sender<()>),
+}
+
 #[derive(Clone)]
 #[cfg_attr(test, derive(Debug))]
 pub struct Logger {
-    tx: mpsc::Sender<String>,
+    tx: mpsc::Sender<Message>,
     log_level: LogLevel,
 }
 
@@ -23,7 +31,7 @@ impl PartialEq for Logger {
 }
 
 impl Logger {
-    pub fn new(tx: mpsc::Sender<String>, log_level: LogLevel) -> Self {
+    pub fn new(tx: mpsc::Sender<Message>, log_level: LogLevel) -> Self {
         Self { tx, log_level }
     }
 
@@ -39,7 +47,16 @@ impl Logger {
     }
 
     async fn send(&mut self, line: String) -> JfResult<()> {
-        self.tx.send(line).await?;
+        self.tx.send(Message::Line(line)).await?;
+        Ok(())
+    }
+
+    /// wait until every line sent so far is written
+    pub async fn flush(&self) -> JfResult<()> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Message::Flush(tx)).await?;
+        // the worker has stopped if the ack is dropped, nothing to wait for
+        let _ = rx.await;
         Ok(())
     }
 
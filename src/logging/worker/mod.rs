@@ -5,7 +5,10 @@ use tokio::sync::mpsc;
 
 use crate::util::error::JfResult;
 
-use super::{logger::Logger, LogLevel};
+use super::{
+    logger::{Logger, Message},
+    LogLevel,
+};
 pub use writer::*;
 
 pub struct Worker {
@@ -18,10 +21,15 @@ impl Worker {
     }
 
     pub async fn start<W: Writer>(&mut self, mut log_writer: W, log_level: LogLevel) -> Logger {
-        let (tx, mut rx) = mpsc::channel::<String>(100);
+        let (tx, mut rx) = mpsc::channel::<Message>(100);
         self.handle = Some(tokio::spawn(async move {
             while let Some(msg) = rx.recv().await {
-                log_writer.write(&msg).await?;
+                match msg {
+                    Message::Line(line) => log_writer.write(&line).await?,
+                    Message::Flush(ack) => {
+                        let _ = ack.send(());
+                    }
+                }
             }
             Ok(())
         }));
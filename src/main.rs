@@ -8,27 +8,28 @@ mod jobdef;
 mod logging;
 mod util;
 
-use clap::Parser;
 use logging::Stdout;
 
 #[tokio::main]
 async fn main() {
-    let args = cli::Args::parse();
+    let args = cli::Args::parse_from_cli(std::env::args_os());
     let mut log_worker = logging::Worker::new();
-    {
+    let exit_code = {
         let logger = log_worker.start(Stdout::new(), args.log_level()).await;
         match cli::Cli::load(logger.clone(), args) {
-            Ok(cli) => {
-                if let Err(e) = cli.run().await {
+            Ok(cli) => match cli.run().await {
+                Ok(exit_code) => exit_code,
+                Err(e) => {
                     eprintln!("Error: {e}");
-                    std::process::exit(1);
+                    1
                 }
-            }
+            },
             Err(e) => {
                 eprintln!("Failed to start: {e}");
-                std::process::exit(1);
+                1
             }
         }
-    }
+    };
     log_worker.join().await.unwrap();
+    std::process::exit(exit_code);
 }
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::util::error::{IntoJfError, JfResult};
+
+const UNITS: [(&str, u64); 4] = [("h", 3_600_000), ("m", 60_000), ("s", 1_000), ("ms", 1)];
+
+/// Parse duration like `500ms`, `30s`, `10m`, `1h` or `1h30m`
+pub fn parse(s: &str) -> JfResult<Duration> {
+    let invalid =
+        || format!("Invalid duration `{s}` (e.g. 500ms, 30s, 10m, 1h30m)").into_jf_error();
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    let mut millis: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value = rest[..digits].parse::<u64>().map_err(|_| invalid())?;
+        let after = &rest[digits..];
+        let unit_len = after
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(after.len());
+        let factor = UNITS
+            .iter()
+            .find(|(unit, _)| *unit == &after[..unit_len])
+            .map(|(_, factor)| *factor)
+            .ok_or_else(invalid)?;
+        millis = value
+            .checked_mul(factor)
+            .and_then(|ms| millis.checked_add(ms))
+            .ok_or_else(invalid)?;
+        rest = &after[unit_len..];
+    }
+    Ok(Duration::from_millis(millis))
+}
+
+/// Format `duration` in the same notation as [`parse`]
+pub fn format(duration: Duration) -> String {
+    let mut millis = duration.as_millis() as u64;
+    if millis == 0 {
+        return "0s".into();
+    }
+    let mut formatted = String::new();
+    for (unit, factor) in UNITS {
+        if millis >= factor {
+            formatted.push_str(&format!("{}{unit}", millis / factor));
+            millis %= factor;
+        }
+    }
+    formatted
+}
+
+/// `deserialize_with` for `Option<Duration>` written as string
+pub fn deserialize_opt<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| parse(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn parse_duration() -> JfResult<()> {
+        assert_eq!(parse("500ms")?, Duration::from_millis(500));
+        assert_eq!(parse("30s")?, Duration::from_secs(30));
+        assert_eq!(parse("10m")?, Duration::from_secs(600));
+        assert_eq!(parse(" 1h30m ")?, Duration::from_secs(5400));
+        assert!(parse("").is_err());
+        assert!(parse("10").is_err());
+        assert!(parse("m").is_err());
+        assert!(parse("10d").is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn format_duration() {
+        assert_eq!(format(Duration::from_millis(500)), "500ms");
+        assert_eq!(format(Duration::from_secs(600)), "10m");
+        assert_eq!(format(Duration::from_millis(5_401_000)), "1h30m1s");
+        assert_eq!(format(Duration::ZERO), "0s");
+    }
+}
@@ -1,4 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
+use crate::cfg::source::Diagnostic;
+
 pub type JfResult<T> = Result<T, JfError>;
 
 #[derive(Debug, thiserror::Error)]
@@ -6,7 +8,7 @@ pub enum JfError {
     #[error("IO error occurred: {0}")]
     IoError(#[from] std::io::Error),
     #[error("Tokio JoinError occurred: {0}")]
-    TokioMpscSendError(#[from] tokio::sync::mpsc::error::SendError<String>),
+    TokioMpscSendError(#[from] tokio::sync::mpsc::error::SendError<crate::logging::Message>),
     #[error("std::sync::mpsc::RecvError occurred: {0}")]
     SyncMpscMpscRecvError(#[from] std::sync::mpsc::RecvError),
     #[error("std::sync::mpsc::RecvTimeoutError occurred: {0}")]
@@ -23,9 +25,14 @@ pub enum JfError {
     NotifyError(#[from] notify::Error),
     #[error("GlobPatternError occurred: {0}")]
     GlobPatternError(#[from] glob::PatternError),
+    #[error("GlobsetError occurred: {0}")]
+    GlobsetError(#[from] globset::Error),
+    #[error("IgnoreError occurred: {0}")]
+    IgnoreError(#[from] ignore::Error),
 
-    #[error("{0:?}")]
-    Multi(Vec<JfError>),
+    /// problems in cfg file with their locations
+    #[error("{}", .0.iter().map(Diagnostic::body).collect::<Vec<_>>().join("\n"))]
+    Cfg(Vec<Diagnostic>),
 
     #[error("{0}")]
     Custom(String),
@@ -52,15 +59,4 @@ mod tests {
         assert_eq!(err.to_string(), "test");
         println!("{err:?}")
     }
-
-    #[test]
-    #[coverage(off)]
-    fn multi() {
-        let err = JfError::Multi(vec![
-            JfError::Custom("test1".into()),
-            JfError::Custom("test2".into()),
-        ]);
-
-        assert_eq!(err.to_string(), "[Custom(\"test1\"), Custom(\"test2\")]");
-    }
 }
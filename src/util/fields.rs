@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MPL-2.0
+use serde::{
+    de::{self, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+/// Deserializer which only records the field names requested by `deserialize_struct`
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// keys accepted by struct `T` derived `Deserialize` (after `rename`) to check key lists
+///
+/// Empty if `T` is not a plain struct (e.g. it has `#[serde(flatten)]` fields).
+pub fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Sample {
+        name: String,
+        #[serde(rename = "type")]
+        sample_type: String,
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn names() {
+        assert_eq!(field_names::<Sample>(), &["name", "type"]);
+        assert!(field_names::<String>().is_empty());
+    }
+}
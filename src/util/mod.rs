@@ -1,6 +1,11 @@
 // SPDX-License-Identifier: MPL-2.0
+pub mod duration;
 pub mod error;
+#[cfg(test)]
+pub mod fields;
 mod read_only;
+pub mod suggest;
+pub mod template;
 #[cfg(test)]
 pub mod testutil;
 
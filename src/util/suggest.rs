@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MPL-2.0
+
+/// number of edits (insertion, deletion, substitution or swap of adjacent chars) to turn `a` into `b`
+fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // d[i][j]: distance between a[..i] and b[..j]
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = d[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = substitution.min(d[i - 1][j] + 1).min(d[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// closest candidate to `input` if it looks like a typo of it
+pub fn did_you_mean<'a, I>(input: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let threshold = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (distance(input, candidate), candidate))
+        .filter(|(d, _)| *d <= threshold)
+        .min() // the first in alphabetical order if tied
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[coverage(off)]
+    fn edit_distance() {
+        assert_eq!(distance("command", "command"), 0);
+        assert_eq!(distance("comand", "command"), 1);
+        assert_eq!(distance("watchlist", "watch_list"), 1);
+        assert_eq!(distance("tset", "test"), 1);
+        assert_eq!(distance("", "abc"), 3);
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn suggest() {
+        let keys = ["command", "args", "watch_list", "cwd"];
+        assert_eq!(did_you_mean("comand", keys), Some("command"));
+        assert_eq!(did_you_mean("watchlist", keys), Some("watch_list"));
+        assert_eq!(did_you_mean("arg", keys), Some("args"));
+        assert_eq!(did_you_mean("script", keys), None);
+    }
+}
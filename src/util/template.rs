@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MPL-2.0
+use std::collections::HashMap;
+
+use crate::util::error::JfResult;
+
+/// Replace `{{name}}` in `template` with the value of `vars`
+///
+/// Spaces inside braces are ignored (`{{ name }}`).
+/// `{{` not followed by a name in `vars` is kept as is (e.g. `{{.Names}}` of docker),
+/// and `{{{{` is rendered as `{{` to keep `{{name}}` itself.
+pub fn render(template: &str, vars: &HashMap<String, String>) -> JfResult<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        if let Some(escaped) = after.strip_prefix("{{") {
+            rendered.push_str("{{");
+            rest = escaped;
+            continue;
+        }
+        let value = after
+            .find("}}")
+            .and_then(|end| Some((vars.get(after[..end].trim())?, end)));
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+pub fn render_all(templates: &[String], vars: &HashMap<String, String>) -> JfResult<Vec<String>> {
+    templates.iter().map(|t| render(t, vars)).collect()
+}
+
+/// quote `value` for shells if it contains characters other than `[A-Za-z0-9_./:=@%+-]`
+pub fn quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_./:=@%+-".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        return value.to_owned();
+    }
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[coverage(off)]
+    fn vars() -> HashMap<String, String> {
+        HashMap::from([
+            ("target".to_owned(), "release".to_owned()),
+            ("name".to_owned(), "jf".to_owned()),
+        ])
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn quote_values() {
+        assert_eq!(quote("src/main.rs"), "src/main.rs");
+        assert_eq!(quote("src/a b.rs"), "'src/a b.rs'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$(rm)"), "'$(rm)'");
+        assert_eq!(quote(""), "''");
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn render_vars() -> JfResult<()> {
+        assert_eq!(render("build", &vars())?, "build");
+        assert_eq!(render("--{{target}}", &vars())?, "--release");
+        assert_eq!(render("{{ name }}-{{target}}", &vars())?, "jf-release");
+        assert_eq!(
+            render_all(&["{{name}}".into(), "x".into()], &vars())?,
+            vec!["jf", "x"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn render_undeclared() -> JfResult<()> {
+        assert_eq!(render("{{unknown}}", &vars())?, "{{unknown}}");
+        assert_eq!(render("{{target", &vars())?, "{{target");
+        assert_eq!(
+            render("docker ps --format '{{.Names}}' {{target}}", &vars())?,
+            "docker ps --format '{{.Names}}' release"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[coverage(off)]
+    fn render_escaped() -> JfResult<()> {
+        assert_eq!(render("{{{{target}}", &vars())?, "{{target}}");
+        assert_eq!(render("{{{{{{target}}", &vars())?, "{{release");
+        assert_eq!(render("{{{{ {{name}}", &vars())?, "{{ jf");
+        Ok(())
+    }
+}